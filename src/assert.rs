@@ -0,0 +1,280 @@
+use std::path::{Path, PathBuf};
+
+use crate::walk::{display_path, listing, walk, GLOB_OPTIONS};
+
+/// Assertions on the content of a [`crate::Tree`].
+///
+/// Every assertion panics with a message listing the actual tree content
+/// when it fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use tree_fs::TreeBuilder;
+/// let tree = TreeBuilder::default()
+///     .add_file("out/report.txt", "status: ok")
+///     .add_directory("out/cache")
+///     .create()
+///     .expect("create tree fs");
+///
+/// tree.assert()
+///     .matches_glob("out/*.txt")
+///     .file("out/report.txt")
+///     .is_file()
+///     .contains("ok");
+/// tree.assert().path("out/cache").is_dir();
+/// tree.assert().path("missing.txt").does_not_exist();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TreeAssert<'a> {
+    root: &'a Path,
+}
+
+// Assertions are called for their panics, the returned value only allows chaining.
+#[allow(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+impl<'a> TreeAssert<'a> {
+    pub(crate) const fn new(root: &'a Path) -> Self {
+        Self { root }
+    }
+
+    /// Starts assertions on the entry at `path`, relative to the root folder.
+    #[must_use]
+    pub fn path<P: AsRef<Path>>(&self, path: P) -> PathAssert<'a> {
+        PathAssert {
+            root: self.root,
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Starts assertions on the entry at `path`, relative to the root folder.
+    ///
+    /// This is an alias for `path`.
+    #[must_use]
+    pub fn file<P: AsRef<Path>>(&self, path: P) -> PathAssert<'a> {
+        self.path(path)
+    }
+
+    /// Asserts that at least one entry matches the glob `pattern`.
+    ///
+    /// Patterns are matched against paths relative to the root folder with
+    /// `/` separators; `*` does not cross directories while `**` does.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `pattern` is invalid or no entry matches it.
+    #[track_caller]
+    pub fn matches_glob(self, pattern: &str) -> Self {
+        let glob = glob::Pattern::new(pattern)
+            .unwrap_or_else(|e| panic!("invalid glob pattern `{pattern}`: {e}"));
+        let matched = walk(self.root)
+            .unwrap_or_default()
+            .iter()
+            .any(|path| glob.matches_with(&display_path(path), GLOB_OPTIONS));
+        if !matched {
+            fail(self.root, &format!("no entry matches `{pattern}`"));
+        }
+        self
+    }
+}
+
+impl TreeAssert<'_> {
+    /// Asserts that the tree matches the `expected` entries, see
+    /// [`crate::compare`].
+    ///
+    /// # Panics
+    ///
+    /// Panics with a report of every difference when the tree does not match.
+    #[track_caller]
+    #[allow(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+    pub fn matches(self, expected: &crate::TreeBuilder, mode: crate::CompareMode) -> Self {
+        match crate::compare(expected, self.root, mode) {
+            Ok(diff) if diff.is_empty() => {}
+            Ok(diff) => fail(self.root, &diff.to_string()),
+            Err(e) => fail(self.root, &format!("comparison failed: {e}")),
+        }
+        self
+    }
+}
+
+/// Assertions on a single entry of a [`crate::Tree`], see [`TreeAssert`].
+#[derive(Debug, Clone)]
+pub struct PathAssert<'a> {
+    root: &'a Path,
+    path: PathBuf,
+}
+
+#[allow(clippy::must_use_candidate, clippy::return_self_not_must_use)]
+impl PathAssert<'_> {
+    /// Asserts that the entry exists. Dangling symlinks count as existing.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the entry does not exist.
+    #[track_caller]
+    pub fn exists(self) -> Self {
+        if std::fs::symlink_metadata(self.full_path()).is_err() {
+            self.fail("does not exist");
+        }
+        self
+    }
+
+    /// Asserts that the entry does not exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the entry exists.
+    #[track_caller]
+    pub fn does_not_exist(self) -> Self {
+        if std::fs::symlink_metadata(self.full_path()).is_ok() {
+            self.fail("exists");
+        }
+        self
+    }
+
+    /// Asserts that the entry is a file, following symlinks.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the entry is not a file.
+    #[track_caller]
+    pub fn is_file(self) -> Self {
+        if !self.full_path().is_file() {
+            self.fail("is not a file");
+        }
+        self
+    }
+
+    /// Asserts that the entry is a directory, following symlinks.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the entry is not a directory.
+    #[track_caller]
+    pub fn is_dir(self) -> Self {
+        if !self.full_path().is_dir() {
+            self.fail("is not a directory");
+        }
+        self
+    }
+
+    /// Asserts that the entry is a symbolic link.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the entry is not a symbolic link.
+    #[track_caller]
+    pub fn is_symlink(self) -> Self {
+        if !self.full_path().is_symlink() {
+            self.fail("is not a symlink");
+        }
+        self
+    }
+
+    /// Asserts that the entry is read-only.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the entry does not exist or is writable.
+    #[track_caller]
+    pub fn is_readonly(self) -> Self {
+        match std::fs::metadata(self.full_path()) {
+            Ok(metadata) if metadata.permissions().readonly() => {}
+            Ok(_) => self.fail("is not read-only"),
+            Err(e) => self.fail(&format!("cannot be read: {e}")),
+        }
+        self
+    }
+
+    /// Asserts that the entry is writable.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the entry does not exist or is read-only.
+    #[track_caller]
+    pub fn is_writable(self) -> Self {
+        match std::fs::metadata(self.full_path()) {
+            Ok(metadata) if !metadata.permissions().readonly() => {}
+            Ok(_) => self.fail("is read-only"),
+            Err(e) => self.fail(&format!("cannot be read: {e}")),
+        }
+        self
+    }
+
+    /// Asserts that the file content contains `needle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the file cannot be read as text or does not contain `needle`.
+    #[track_caller]
+    pub fn contains(self, needle: &str) -> Self {
+        let content = self.read_to_string();
+        if !content.contains(needle) {
+            self.fail(&format!(
+                "does not contain {needle:?}\n\nactual content:\n{content}"
+            ));
+        }
+        self
+    }
+
+    /// Asserts that the file content is exactly `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the file cannot be read as text or its content differs.
+    #[track_caller]
+    pub fn has_content(self, expected: &str) -> Self {
+        let content = self.read_to_string();
+        if content != expected {
+            self.fail(&format!(
+                "has unexpected content\n\nexpected:\n{expected}\n\nactual:\n{content}"
+            ));
+        }
+        self
+    }
+
+    /// Asserts that the file content is exactly `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the file cannot be read or its content differs.
+    #[track_caller]
+    pub fn has_bytes<C: AsRef<[u8]>>(self, expected: C) -> Self {
+        match std::fs::read(self.full_path()) {
+            Ok(content) if content == expected.as_ref() => {}
+            Ok(content) => self.fail(&format!(
+                "has unexpected content: expected {} bytes, found {} bytes",
+                expected.as_ref().len(),
+                content.len()
+            )),
+            Err(e) => self.fail(&format!("cannot be read: {e}")),
+        }
+        self
+    }
+
+    fn full_path(&self) -> PathBuf {
+        self.root.join(&self.path)
+    }
+
+    #[track_caller]
+    fn read_to_string(&self) -> String {
+        std::fs::read_to_string(self.full_path())
+            .unwrap_or_else(|e| self.fail(&format!("cannot be read as text: {e}")))
+    }
+
+    #[track_caller]
+    fn fail(&self, message: &str) -> ! {
+        fail(
+            self.root,
+            &format!("`{}` {message}", display_path(&self.path)),
+        )
+    }
+}
+
+#[track_caller]
+fn fail(root: &Path, message: &str) -> ! {
+    panic!(
+        "tree assertion failed: {message}\n\ntree content at {}:\n{}",
+        root.display(),
+        listing(root)
+    )
+}
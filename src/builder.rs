@@ -4,9 +4,13 @@ use std::{
     path::{Path, PathBuf},
 };
 
-#[cfg(feature = "yaml")]
+#[cfg(feature = "spec")]
 use serde::Deserialize;
 
+use crate::error::{Operation, ResultExt};
+use crate::template::Vars;
+use crate::tree::TempRoot;
+
 /// Represents a file tree structure
 ///
 /// # Examples
@@ -50,33 +54,106 @@ use serde::Deserialize;
 /// assert!(!path_to_check.exists(), "Directory should be deleted after drop");
 /// ```
 #[derive(Debug)]
-#[cfg_attr(feature = "yaml", derive(Deserialize))]
+#[cfg_attr(feature = "spec", derive(Deserialize))]
+#[cfg_attr(feature = "spec", serde(from = "tree_fs_spec::TreeSpec"))]
 pub struct TreeBuilder {
     /// Root folder where the tree will be created.
-    #[cfg_attr(feature = "yaml", serde(default = "crate::tree::temp_dir"))]
+    ///
+    /// For temporary roots this is the first candidate; if it is taken by the
+    /// time the tree is created another name is picked, so always use
+    /// [`crate::Tree::root`] once the tree exists. Assigning another path
+    /// makes it an explicit root, like [`Self::root_folder`].
     pub root: PathBuf,
+    /// Naming of the temporary root folder, `None` when the root was set explicitly.
+    pub(crate) temp_root: Option<TempRoot>,
     /// Flag indicating whether existing files should be overridden.
-    #[cfg_attr(feature = "yaml", serde(default))]
-    override_file: bool,
+    pub(crate) override_file: bool,
     /// List of entries in the tree.
-    entries: Vec<crate::Entry>,
-    /// Whether to automatically delete the temporary folder when Tree is dropped
-    #[cfg_attr(feature = "yaml", serde(default = "crate::yaml::default_drop"))]
-    drop: bool,
+    pub(crate) entries: Vec<crate::Entry>,
+    /// When to automatically delete the root folder when Tree is dropped
+    pub(crate) drop: crate::DropPolicy,
+    /// Whether relative symlink targets are resolved against the root folder.
+    pub(crate) resolve_link_targets: bool,
+    /// Whether entries may be written outside the root folder.
+    allow_unsafe_paths: bool,
+    /// Variables substituted in entry paths and text content.
+    pub(crate) vars: Vars,
 }
 
 impl TreeBuilder {
     /// Sets the root folder where the tree will be created.
+    ///
+    /// Unlike temporary roots, an existing folder is reused as is.
     #[must_use]
     pub fn root_folder<P: AsRef<Path>>(mut self, dir: P) -> Self {
         self.root = dir.as_ref().to_path_buf();
+        self.temp_root = None;
         self
     }
 
+    /// Sets the prefix of the temporary root folder name.
+    ///
+    /// Switches back to a temporary root if one was set with `root_folder`.
+    #[must_use]
+    pub fn temp_prefix<S: Into<String>>(self, prefix: S) -> Self {
+        self.with_temp_root(|temp| temp.prefix = prefix.into())
+    }
+
+    /// Sets the suffix of the temporary root folder name.
+    ///
+    /// Switches back to a temporary root if one was set with `root_folder`.
+    #[must_use]
+    pub fn temp_suffix<S: Into<String>>(self, suffix: S) -> Self {
+        self.with_temp_root(|temp| temp.suffix = suffix.into())
+    }
+
+    /// Sets the folder in which the temporary root folder is created, instead
+    /// of the system temporary folder.
+    ///
+    /// Switches back to a temporary root if one was set with `root_folder`.
+    #[must_use]
+    pub fn temp_parent<P: AsRef<Path>>(self, dir: P) -> Self {
+        self.with_temp_root(|temp| temp.parent = Some(dir.as_ref().to_path_buf()))
+    }
+
+    fn with_temp_root(mut self, configure: impl FnOnce(&mut TempRoot)) -> Self {
+        let mut temp = self.temp_root.take().unwrap_or_default();
+        configure(&mut temp);
+        temp.first = temp.candidate();
+        self.root.clone_from(&temp.first);
+        self.temp_root = Some(temp);
+        self
+    }
+
+    /// The temporary root naming, unless `root` was since set explicitly,
+    /// either with `root_folder` or by assigning the field.
+    pub(crate) fn temp_root(&self) -> Option<&TempRoot> {
+        self.temp_root
+            .as_ref()
+            .filter(|temp| temp.first == self.root)
+    }
+
     /// Sets the `drop` flag, indicating whether to automatically delete the temporary folder when the `tree_fs` instance is dropped
+    ///
+    /// `true` maps to [`crate::DropPolicy::Always`] and `false` to
+    /// [`crate::DropPolicy::Never`].
     #[must_use]
     pub const fn drop(mut self, yes: bool) -> Self {
-        self.drop = yes;
+        self.drop = if yes {
+            crate::DropPolicy::Always
+        } else {
+            crate::DropPolicy::Never
+        };
+        self
+    }
+
+    /// Sets when the root folder is deleted once the `tree_fs` instance is dropped.
+    ///
+    /// Use [`crate::DropPolicy::OnSuccess`] to keep the tree for inspection
+    /// when a test panics.
+    #[must_use]
+    pub const fn drop_policy(mut self, policy: crate::DropPolicy) -> Self {
+        self.drop = policy;
         self
     }
 
@@ -87,6 +164,85 @@ impl TreeBuilder {
         self
     }
 
+    /// Sets the `resolve_link_targets` flag, indicating whether relative
+    /// symlink targets are resolved against the root folder.
+    ///
+    /// When disabled, targets are written verbatim, which allows creating
+    /// deliberately broken or relative links.
+    #[must_use]
+    pub const fn resolve_link_targets(mut self, yes: bool) -> Self {
+        self.resolve_link_targets = yes;
+        self
+    }
+
+    /// Sets the `allow_unsafe_paths` flag, disabling the check that every
+    /// entry stays inside the root folder.
+    ///
+    /// By default absolute paths, `..` escapes and paths traversing symlinks
+    /// that point outside the root are rejected before anything is written.
+    /// This flag can only be set from code, never from a YAML spec.
+    #[must_use]
+    pub const fn allow_unsafe_paths(mut self, yes: bool) -> Self {
+        self.allow_unsafe_paths = yes;
+        self
+    }
+
+    /// Sets a variable substituted for `{{key}}` in entry paths and text file
+    /// content.
+    ///
+    /// `{{root}}` is always defined as the root folder of the tree. Unknown
+    /// placeholders are left as they are unless [`strict_vars`](Self::strict_vars)
+    /// is enabled, and nothing is substituted when
+    /// [`templating`](Self::templating) is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let tree = tree_fs::TreeBuilder::default()
+    ///     .var("name", "demo")
+    ///     .add_file("{{name}}/config.toml", "name = \"{{name}}\"\ndata = \"{{root}}/data\"")
+    ///     .create()
+    ///     .expect("create tree fs");
+    ///
+    /// let config = std::fs::read_to_string(tree.root.join("demo/config.toml")).unwrap();
+    /// assert!(config.starts_with("name = \"demo\""));
+    /// ```
+    #[must_use]
+    pub fn var<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.vars.values.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the `strict_vars` flag, making placeholders that name an undefined
+    /// variable an error instead of leaving them as they are.
+    #[must_use]
+    pub const fn strict_vars(mut self, yes: bool) -> Self {
+        self.vars.strict = yes;
+        self
+    }
+
+    /// Sets whether `{{name}}` placeholders, including `{{root}}`, are
+    /// substituted. Enabled by default; disable it to write content that
+    /// contains placeholders of its own verbatim.
+    #[must_use]
+    pub const fn templating(mut self, yes: bool) -> Self {
+        self.vars.enabled = yes;
+        self
+    }
+
+    /// Returns the entries added to the tree so far.
+    #[must_use]
+    pub fn entries(&self) -> &[crate::Entry] {
+        &self.entries
+    }
+
+    /// Adds an entry of any kind to the tree.
+    #[must_use]
+    pub fn add_entry(mut self, entry: crate::Entry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
     /// Adds a file with content to the tree.
     #[must_use]
     pub fn add<P: AsRef<Path>>(mut self, path: P, content: &str) -> Self {
@@ -125,6 +281,19 @@ impl TreeBuilder {
         self
     }
 
+    /// Adds a file with binary content to the tree.
+    #[must_use]
+    pub fn add_binary_file<P: AsRef<Path>, C: AsRef<[u8]>>(mut self, path: P, content: C) -> Self {
+        self.entries.push(crate::Entry {
+            path: path.as_ref().to_path_buf(),
+            kind: crate::Kind::BinaryFile {
+                content: content.as_ref().to_vec(),
+            },
+            settings: None,
+        });
+        self
+    }
+
     /// Adds a directory to the tree.
     #[must_use]
     pub fn add_directory<P: AsRef<Path>>(mut self, path: P) -> Self {
@@ -136,13 +305,123 @@ impl TreeBuilder {
         self
     }
 
+    /// Adds a symbolic link pointing to `target`.
+    #[must_use]
+    pub fn add_symlink<P: AsRef<Path>, T: AsRef<Path>>(mut self, path: P, target: T) -> Self {
+        self.entries.push(crate::Entry {
+            path: path.as_ref().to_path_buf(),
+            kind: crate::Kind::Symlink {
+                target: target.as_ref().to_path_buf(),
+            },
+            settings: None,
+        });
+        self
+    }
+
+    /// Adds a hard link to `target`, which must be created before this entry.
+    #[must_use]
+    pub fn add_hard_link<P: AsRef<Path>, T: AsRef<Path>>(mut self, path: P, target: T) -> Self {
+        self.entries.push(crate::Entry {
+            path: path.as_ref().to_path_buf(),
+            kind: crate::Kind::HardLink {
+                target: target.as_ref().to_path_buf(),
+            },
+            settings: None,
+        });
+        self
+    }
+
+    /// Adds a file whose content is streamed from `generator` when the tree is
+    /// created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tree_fs::{Generator, TreeBuilder};
+    /// let tree = TreeBuilder::default()
+    ///     .add_generated("big.log", Generator::Lorem { lines: 10_000 })
+    ///     .add_generated("disk.img", Generator::Sparse { size: 1 << 20 })
+    ///     .create()
+    ///     .expect("create tree fs");
+    /// assert_eq!(std::fs::metadata(tree.root.join("disk.img")).unwrap().len(), 1 << 20);
+    /// ```
+    #[must_use]
+    pub fn add_generated<P: AsRef<Path>>(mut self, path: P, generator: crate::Generator) -> Self {
+        self.entries.push(crate::Entry {
+            path: path.as_ref().to_path_buf(),
+            kind: crate::Kind::Generated { generator },
+            settings: None,
+        });
+        self
+    }
+
+    /// Adds a copy of the file or directory at `source`, see [`crate::Kind::Copy`].
+    ///
+    /// Entries added afterwards are written over the copied content, even when
+    /// `override_file` is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # let sample = tree_fs::TreeBuilder::default()
+    /// #     .add_file("Cargo.toml", "[package]")
+    /// #     .add_file("src/main.rs", "fn main() {}")
+    /// #     .create()
+    /// #     .expect("create sample");
+    /// let tree = tree_fs::TreeBuilder::default()
+    ///     .copy_from(&sample.root, "project")
+    ///     .add_file("project/src/main.rs", "fn main() { todo!() }")
+    ///     .create()
+    ///     .expect("create tree fs");
+    /// assert!(tree.root.join("project/Cargo.toml").exists());
+    /// ```
+    #[must_use]
+    pub fn copy_from<S: AsRef<Path>, P: AsRef<Path>>(mut self, source: S, path: P) -> Self {
+        self.entries.push(crate::Entry {
+            path: path.as_ref().to_path_buf(),
+            kind: crate::Kind::Copy {
+                source: source.as_ref().to_path_buf(),
+                follow_symlinks: false,
+            },
+            settings: None,
+        });
+        self
+    }
+
+    /// Adds a recursive copy of the directory at `source`.
+    ///
+    /// This is an alias for `copy_from`.
+    #[must_use]
+    pub fn copy_dir_from<S: AsRef<Path>, P: AsRef<Path>>(self, source: S, path: P) -> Self {
+        self.copy_from(source, path)
+    }
+
+    /// Adds a copy of the file or directory at `source`, copying what symlinks
+    /// point to instead of the links themselves.
+    #[must_use]
+    pub fn copy_from_following_symlinks<S: AsRef<Path>, P: AsRef<Path>>(
+        mut self,
+        source: S,
+        path: P,
+    ) -> Self {
+        self.entries.push(crate::Entry {
+            path: path.as_ref().to_path_buf(),
+            kind: crate::Kind::Copy {
+                source: source.as_ref().to_path_buf(),
+                follow_symlinks: true,
+            },
+            settings: None,
+        });
+        self
+    }
+
     /// Adds a file with content and custom settings to the tree.
     #[must_use]
     pub fn add_file_with_settings<P: AsRef<Path>>(
         mut self,
         path: P,
         content: &str,
-        settings: crate::tree::Settings,
+        settings: crate::Settings,
     ) -> Self {
         self.entries.push(crate::Entry {
             path: path.as_ref().to_path_buf(),
@@ -154,12 +433,30 @@ impl TreeBuilder {
         self
     }
 
+    /// Adds a file with binary content and custom settings to the tree.
+    #[must_use]
+    pub fn add_binary_file_with_settings<P: AsRef<Path>, C: AsRef<[u8]>>(
+        mut self,
+        path: P,
+        content: C,
+        settings: crate::Settings,
+    ) -> Self {
+        self.entries.push(crate::Entry {
+            path: path.as_ref().to_path_buf(),
+            kind: crate::Kind::BinaryFile {
+                content: content.as_ref().to_vec(),
+            },
+            settings: Some(settings),
+        });
+        self
+    }
+
     /// Adds an empty file with custom settings to the tree.
     #[must_use]
     pub fn add_empty_file_with_settings<P: AsRef<Path>>(
         mut self,
         path: P,
-        settings: crate::tree::Settings,
+        settings: crate::Settings,
     ) -> Self {
         self.entries.push(crate::Entry {
             path: path.as_ref().to_path_buf(),
@@ -174,7 +471,7 @@ impl TreeBuilder {
     pub fn add_directory_with_settings<P: AsRef<Path>>(
         mut self,
         path: P,
-        settings: crate::tree::Settings,
+        settings: crate::Settings,
     ) -> Self {
         self.entries.push(crate::Entry {
             path: path.as_ref().to_path_buf(),
@@ -187,69 +484,282 @@ impl TreeBuilder {
     /// Convenience method for adding a read-only file.
     #[must_use]
     pub fn add_readonly_file<P: AsRef<Path>>(self, path: P, content: &str) -> Self {
-        self.add_file_with_settings(path, content, crate::tree::Settings::new().readonly(true))
+        self.add_file_with_settings(path, content, crate::Settings::new().readonly(true))
     }
 
     /// Convenience method for adding a read-only empty file.
     #[must_use]
     pub fn add_readonly_empty_file<P: AsRef<Path>>(self, path: P) -> Self {
-        self.add_empty_file_with_settings(path, crate::tree::Settings::new().readonly(true))
+        self.add_empty_file_with_settings(path, crate::Settings::new().readonly(true))
+    }
+
+    /// Checks the entries without touching the file system, other than
+    /// looking for existing symlinks under the root folder.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::Error::UnsafePath`] when an entry would be written
+    /// outside the root folder (unless
+    /// [`allow_unsafe_paths`](Self::allow_unsafe_paths) is set), or
+    /// [`crate::Error::Conflicts`] listing every duplicate path, file vs
+    /// directory conflict and file declared as the ancestor of another entry.
+    /// Returns [`crate::Error::UndefinedVariable`] for undefined variables when
+    /// [`strict_vars`](Self::strict_vars) is set.
+    pub fn validate(&self) -> crate::Result<()> {
+        self.check(&self.rendered_entries(&self.root)?)
+    }
+
+    fn check(&self, entries: &[crate::Entry]) -> crate::Result<()> {
+        if self.allow_unsafe_paths {
+            Ok(())
+        } else {
+            crate::validate::check_paths(&self.root, entries, self.resolve_link_targets)
+        }
+        .and_then(|()| tree_fs_spec::check_conflicts(entries).map_err(crate::Error::Conflicts))
+        .map_err(|e| self.declared_error(entries, e))
     }
 
     /// Creates the file tree by generating files and directories based on the specified metadata.
     ///
     /// # Errors
     ///
-    /// Returns an `std::io::Result` indicating success or failure in creating the file tree.
-    pub fn create(&self) -> std::io::Result<crate::Tree> {
-        if !self.root.exists() {
-            std::fs::create_dir_all(&self.root)?;
+    /// Returns the [`validate`](Self::validate) error before anything is
+    /// written when the entries are invalid, or an [`crate::Error`] naming the
+    /// entry and the operation that failed while creating the file tree.
+    pub fn create(&self) -> crate::Result<crate::Tree> {
+        let mut entries = self.rendered_entries(&self.root)?;
+        self.check(&entries)?;
+
+        // Only paths that existed before this call are protected by
+        // `override_file`, so entries can refine what an earlier copy wrote.
+        // Temporary roots are always fresh.
+        let preexisting: Vec<bool> = entries
+            .iter()
+            .map(|entry| {
+                self.temp_root().is_none()
+                    && std::fs::symlink_metadata(self.root.join(&entry.path)).is_ok()
+            })
+            .collect();
+
+        let root = if let Some(temp) = self.temp_root() {
+            temp.allocate().context(Operation::CreateRoot, &self.root)?
+        } else {
+            if !self.root.exists() {
+                std::fs::create_dir_all(&self.root).context(Operation::CreateRoot, &self.root)?;
+            }
+            self.root.clone()
+        };
+
+        if let Err(error) = self.populate(&root, &mut entries, &preexisting) {
+            // A temporary root is ours alone, so don't leave a partial tree
+            // behind. An explicit root may hold content we must not touch.
+            if self.temp_root().is_some() {
+                let _ = crate::tree::remove_tree(&root);
+            }
+            return Err(error);
+        }
+
+        Ok(crate::Tree {
+            root,
+            drop: self.drop,
+        })
+    }
+
+    /// Renders `entries` for the allocated `root` and writes them.
+    fn populate(
+        &self,
+        root: &Path,
+        entries: &mut Vec<crate::Entry>,
+        preexisting: &[bool],
+    ) -> crate::Result<()> {
+        if root != self.root {
+            *entries = self.rendered_entries(root)?;
         }
+        let canonical_root = if self.allow_unsafe_paths {
+            None
+        } else {
+            Some(root.canonicalize().context(Operation::CreateRoot, root)?)
+        };
+
+        self.write_entries(root, entries, preexisting, canonical_root.as_deref())
+            .map_err(|e| self.declared_error(entries, e))
+    }
+
+    /// Writes the rendered `entries` under `root`, then applies their settings.
+    fn write_entries(
+        &self,
+        root: &Path,
+        entries: &[crate::Entry],
+        preexisting: &[bool],
+        canonical_root: Option<&Path>,
+    ) -> crate::Result<()> {
+        let now = std::time::SystemTime::now();
+        let mut pending = Vec::new();
 
         // Process entries
-        for entry in &self.entries {
-            let dest_path = self.root.join(&entry.path);
-            if !self.override_file && dest_path.exists() {
+        for (entry, preexisting) in entries.iter().zip(preexisting) {
+            if !self.override_file && *preexisting {
                 continue;
             }
+            let dest_path = root.join(&entry.path);
+            let exists = std::fs::symlink_metadata(&dest_path).is_ok();
+            if let Some(canonical_root) = canonical_root {
+                crate::validate::check_destination(canonical_root, root, entry, &dest_path)?;
+            }
 
-            match &entry.kind {
-                crate::Kind::Directory => {
-                    std::fs::create_dir_all(&dest_path)?;
+            let mut copied = Vec::new();
+            self.create_entry(root, entry, &dest_path, exists, &mut copied)?;
+            if matches!(
+                entry.kind,
+                crate::Kind::Symlink { .. } | crate::Kind::HardLink { .. }
+            ) {
+                // The link replaced whatever was copied at its path.
+                pending.retain(|(_, dest, pending)| {
+                    !matches!(pending, Pending::Copied(_)) || *dest != dest_path
+                });
+            }
+            pending.extend(
+                copied
+                    .into_iter()
+                    .map(|(dest, permissions)| (&entry.path, dest, Pending::Copied(permissions))),
+            );
+
+            if let Some(settings) = &entry.settings {
+                // Permissions would be applied to the link target, not the link itself.
+                if !matches!(entry.kind, crate::Kind::Symlink { .. }) {
+                    pending.push((&entry.path, dest_path, Pending::Settings(settings)));
                 }
-                crate::Kind::EmptyFile => {
-                    if let Some(parent_dir) = Path::new(&dest_path).parent() {
-                        std::fs::create_dir_all(parent_dir)?;
-                    }
-                    File::create(&dest_path)?;
+            }
+        }
+
+        // Settings and copied permissions are applied once everything is
+        // written and deepest-first, so a restrictive directory mode cannot
+        // block the creation of its children and writing a child cannot
+        // clobber the timestamps of its parent. The sort is stable, so at the
+        // same path they still apply in declaration order.
+        pending.sort_by_key(|(_, dest_path, _)| std::cmp::Reverse(dest_path.components().count()));
+        for (path, dest_path, pending) in pending {
+            match pending {
+                Pending::Settings(settings) => {
+                    crate::tree::apply_times(settings, &dest_path, now)
+                        .context(Operation::SetTimes, path)?;
+                    crate::tree::apply_permissions(settings, &dest_path)
+                        .context(Operation::SetPermissions, path)?;
                 }
-                crate::Kind::TextFile { content } => {
-                    if let Some(parent_dir) = Path::new(&dest_path).parent() {
-                        std::fs::create_dir_all(parent_dir)?;
+                Pending::Copied(permissions) => {
+                    // A later copy may have put a symlink there, which must
+                    // not be followed.
+                    let is_symlink = std::fs::symlink_metadata(&dest_path)
+                        .is_ok_and(|metadata| metadata.file_type().is_symlink());
+                    if !is_symlink {
+                        std::fs::set_permissions(&dest_path, permissions)
+                            .context(Operation::SetPermissions, path)?;
                     }
-                    let mut file = File::create(&dest_path)?;
-                    file.write_all(content.as_bytes())?;
                 }
             }
+        }
 
-            if let Some(settings) = &entry.settings {
-                if matches!(entry.kind, crate::Kind::Directory) {
-                    continue;
-                }
+        Ok(())
+    }
+
+    /// Writes a single entry to `dest_path`, replacing existing links when `exists`.
+    fn create_entry(
+        &self,
+        root: &Path,
+        entry: &crate::Entry,
+        dest_path: &Path,
+        exists: bool,
+        copied: &mut Vec<(PathBuf, std::fs::Permissions)>,
+    ) -> crate::Result<()> {
+        if !matches!(entry.kind, crate::Kind::Directory) {
+            if let Some(parent_dir) = dest_path.parent() {
+                std::fs::create_dir_all(parent_dir).context(Operation::CreateDir, &entry.path)?;
+            }
+        }
 
-                let dest_path_for_perms = self.root.join(&entry.path);
-                if settings.readonly {
-                    let mut permissions = std::fs::metadata(&dest_path_for_perms)?.permissions();
-                    permissions.set_readonly(true);
-                    std::fs::set_permissions(&dest_path_for_perms, permissions)?;
+        match &entry.kind {
+            crate::Kind::Directory => {
+                std::fs::create_dir_all(dest_path).context(Operation::CreateDir, &entry.path)?;
+            }
+            crate::Kind::EmptyFile => {
+                File::create(dest_path).context(Operation::Write, &entry.path)?;
+            }
+            crate::Kind::TextFile { content } => {
+                File::create(dest_path)
+                    .and_then(|mut file| file.write_all(content.as_bytes()))
+                    .context(Operation::Write, &entry.path)?;
+            }
+            crate::Kind::BinaryFile { content } => {
+                File::create(dest_path)
+                    .and_then(|mut file| file.write_all(content))
+                    .context(Operation::Write, &entry.path)?;
+            }
+            crate::Kind::Symlink { target } => {
+                if exists {
+                    std::fs::remove_file(dest_path).context(Operation::CreateLink, &entry.path)?;
                 }
+                let target = if self.resolve_link_targets {
+                    root.join(target)
+                } else {
+                    target.clone()
+                };
+                symlink(&target, dest_path).context(Operation::CreateLink, &entry.path)?;
+            }
+            crate::Kind::HardLink { target } => {
+                if exists {
+                    std::fs::remove_file(dest_path).context(Operation::CreateLink, &entry.path)?;
+                }
+                std::fs::hard_link(root.join(target), dest_path)
+                    .context(Operation::CreateLink, &entry.path)?;
+            }
+            crate::Kind::Generated { generator } => {
+                File::create(dest_path)
+                    .and_then(|file| crate::generate::write_file(generator, file))
+                    .context(Operation::Write, &entry.path)?;
+            }
+            crate::Kind::Copy {
+                source,
+                follow_symlinks,
+            } => {
+                // The root keeps its own permissions when a directory is copied into it.
+                let into_root = crate::validate::normalize(&entry.path)
+                    .is_some_and(|path| path.as_os_str().is_empty());
+                crate::copy::copy(source, dest_path, *follow_symlinks, !into_root, copied)
+                    .context(Operation::Copy, &entry.path)?;
             }
         }
 
-        Ok(crate::Tree {
-            root: self.root.clone(),
-            drop: self.drop,
-        })
+        Ok(())
+    }
+}
+
+/// Work left for once every entry is written.
+enum Pending<'a> {
+    /// Settings declared on the entry.
+    Settings(&'a crate::Settings),
+    /// Permissions of a copied source.
+    Copied(std::fs::Permissions),
+}
+
+/// Creates a symbolic link at `link` pointing to `target`.
+#[cfg(unix)]
+pub fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Creates a symbolic link at `link` pointing to `target`.
+///
+/// Windows distinguishes between file and directory links, so the kind is
+/// picked from the target when it exists and defaults to a file link.
+#[cfg(windows)]
+pub fn symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    let resolved = link
+        .parent()
+        .map_or_else(|| target.to_path_buf(), |p| p.join(target));
+    if resolved.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
     }
 }
 
@@ -259,8 +769,13 @@ impl Default for TreeBuilder {
         Self {
             entries: vec![],
             override_file: false,
-            root: crate::tree::temp_dir(),
-            drop: true,
+            root: PathBuf::new(),
+            temp_root: None,
+            drop: crate::DropPolicy::Always,
+            resolve_link_targets: true,
+            allow_unsafe_paths: false,
+            vars: Vars::default(),
         }
+        .with_temp_root(|_| {})
     }
 }
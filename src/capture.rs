@@ -0,0 +1,263 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    error::{Operation, ResultExt},
+    walk::{display_path, GLOB_OPTIONS},
+    Entry, Error, Kind, Settings, TreeBuilder,
+};
+
+/// Options for capturing an existing directory with
+/// [`TreeBuilder::from_dir_with`].
+///
+/// Glob patterns are matched against paths relative to the captured directory
+/// with `/` separators; `*` does not cross directories while `**` does.
+///
+/// # Examples
+///
+/// ```rust
+/// use tree_fs::{CaptureOptions, TreeBuilder};
+/// let tree = TreeBuilder::default()
+///     .add_file("src/main.rs", "fn main() {}")
+///     .add_file("target/debug/app", "binary")
+///     .create()
+///     .expect("create tree fs");
+///
+/// let options = CaptureOptions::new()
+///     .exclude("target")
+///     .max_file_size(1024 * 1024);
+/// let snapshot = TreeBuilder::from_dir_with(&tree.root, &options).expect("capture");
+/// let copy = snapshot.create().expect("create copy");
+/// assert!(copy.root.join("src/main.rs").exists());
+/// assert!(!copy.root.join("target").exists());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CaptureOptions {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    max_file_size: Option<u64>,
+    settings: bool,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            include: vec![],
+            exclude: vec![],
+            max_file_size: None,
+            settings: true,
+        }
+    }
+}
+
+impl CaptureOptions {
+    /// Creates options capturing everything, including settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only captures entries matching `pattern`, or any other include
+    /// pattern. Directories are still walked to find matching entries.
+    #[must_use]
+    pub fn include<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Skips entries matching `pattern`, including everything below excluded
+    /// directories.
+    #[must_use]
+    pub fn exclude<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Skips files larger than `bytes`.
+    #[must_use]
+    pub const fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Sets whether permissions are captured as entry settings, which is the
+    /// default. Disable it for specs that must not depend on the platform or
+    /// umask.
+    #[must_use]
+    pub const fn settings(mut self, yes: bool) -> Self {
+        self.settings = yes;
+        self
+    }
+}
+
+fn matches_any(patterns: &[glob::Pattern], path: &Path) -> bool {
+    let path = display_path(path);
+    patterns
+        .iter()
+        .any(|pattern| pattern.matches_with(&path, GLOB_OPTIONS))
+}
+
+/// Compiled form of [`CaptureOptions`].
+struct Capture<'a> {
+    root: &'a Path,
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+    options: &'a CaptureOptions,
+}
+
+impl TreeBuilder {
+    /// Captures the directory at `path` into a new builder, with every file,
+    /// directory and symlink as an entry and their permissions as settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the directory cannot be read.
+    pub fn from_dir<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        Self::from_dir_with(path, &CaptureOptions::default())
+    }
+
+    /// Captures the directory at `path` into a new builder, filtered by
+    /// `options`.
+    ///
+    /// Files are captured as empty, text or binary files depending on their
+    /// content and symlinks keep their target verbatim. Directories are only
+    /// captured when they have no captured children or carry settings; other
+    /// directories are implied by their children. The builder uses a fresh
+    /// temporary root, and templating is disabled so captured content is
+    /// recreated verbatim.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when a glob pattern is invalid or the directory cannot
+    /// be read.
+    pub fn from_dir_with<P: AsRef<Path>>(path: P, options: &CaptureOptions) -> crate::Result<Self> {
+        let compile = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    glob::Pattern::new(pattern).map_err(|e| Error::InvalidGlob {
+                        pattern: pattern.clone(),
+                        message: e.to_string(),
+                    })
+                })
+                .collect::<crate::Result<Vec<_>>>()
+        };
+        let capture = Capture {
+            root: path.as_ref(),
+            include: compile(&options.include)?,
+            exclude: compile(&options.exclude)?,
+            options,
+        };
+
+        let mut builder = Self::default()
+            .resolve_link_targets(false)
+            .templating(false);
+        capture.dir(Path::new(""), &mut builder.entries)?;
+        Ok(builder)
+    }
+}
+
+impl Capture<'_> {
+    /// Captures the children of `relative`, returning whether any was captured.
+    fn dir(&self, relative: &Path, entries: &mut Vec<Entry>) -> crate::Result<bool> {
+        let full_path = self.root.join(relative);
+        let mut children = std::fs::read_dir(&full_path)
+            .and_then(|dir| {
+                dir.map(|entry| entry.map(|e| e.file_name()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .context(Operation::Read, &full_path)?;
+        children.sort();
+
+        let mut captured_any = false;
+        for name in children {
+            let path = relative.join(name);
+            if matches_any(&self.exclude, &path) {
+                continue;
+            }
+            captured_any |= self.entry(path, entries)?;
+        }
+        Ok(captured_any)
+    }
+
+    /// Captures a single entry, returning whether it was captured.
+    fn entry(&self, path: PathBuf, entries: &mut Vec<Entry>) -> crate::Result<bool> {
+        let full_path = self.root.join(&path);
+        let metadata =
+            std::fs::symlink_metadata(&full_path).context(Operation::Read, &full_path)?;
+        let included = self.include.is_empty() || matches_any(&self.include, &path);
+
+        if metadata.is_dir() {
+            let position = entries.len();
+            let has_children = self.dir(&path, entries)?;
+            let settings = self.settings(&metadata);
+            if included && (!has_children || settings.is_some()) {
+                entries.insert(
+                    position,
+                    Entry {
+                        path,
+                        kind: Kind::Directory,
+                        settings,
+                    },
+                );
+                return Ok(true);
+            }
+            return Ok(has_children);
+        }
+
+        if !included {
+            return Ok(false);
+        }
+
+        let (kind, settings) = if metadata.file_type().is_symlink() {
+            let target = std::fs::read_link(&full_path).context(Operation::Read, &full_path)?;
+            (Kind::Symlink { target }, None)
+        } else {
+            if self
+                .options
+                .max_file_size
+                .is_some_and(|max| metadata.len() > max)
+            {
+                return Ok(false);
+            }
+            let content = std::fs::read(&full_path).context(Operation::Read, &full_path)?;
+            let kind = if content.is_empty() {
+                Kind::EmptyFile
+            } else {
+                match String::from_utf8(content) {
+                    Ok(content) => Kind::TextFile { content },
+                    Err(e) => Kind::BinaryFile {
+                        content: e.into_bytes(),
+                    },
+                }
+            };
+            (kind, self.settings(&metadata))
+        };
+
+        entries.push(Entry {
+            path,
+            kind,
+            settings,
+        });
+        Ok(true)
+    }
+
+    fn settings(&self, metadata: &std::fs::Metadata) -> Option<Settings> {
+        if !self.options.settings {
+            return None;
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            Some(Settings::new().mode(metadata.permissions().mode() & 0o7777))
+        }
+
+        #[cfg(not(unix))]
+        {
+            metadata
+                .permissions()
+                .readonly()
+                .then(|| Settings::new().readonly(true))
+        }
+    }
+}
@@ -0,0 +1,422 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::{Operation, ResultExt},
+    validate::normalize,
+    walk::{display_path, walk},
+    Entry, Kind, Settings, TreeBuilder,
+};
+
+/// How strictly the actual directory must match the expected entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompareMode {
+    /// The directory must contain exactly the expected entries, together with
+    /// the directories leading to them.
+    #[default]
+    Exact,
+    /// The directory must contain at least the expected entries; anything
+    /// else is ignored.
+    AtLeast,
+}
+
+/// A single difference between the expected and the actual tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// An expected entry does not exist.
+    Missing {
+        /// Path relative to the root.
+        path: PathBuf,
+    },
+    /// An entry exists but is not expected, only reported in
+    /// [`CompareMode::Exact`].
+    Extra {
+        /// Path relative to the root.
+        path: PathBuf,
+    },
+    /// An entry exists with a different kind, e.g. a directory instead of a
+    /// file.
+    KindMismatch {
+        /// Path relative to the root.
+        path: PathBuf,
+        /// The expected kind.
+        expected: &'static str,
+        /// The actual kind.
+        actual: &'static str,
+    },
+    /// A file has different content, or a symlink a different target.
+    ContentMismatch {
+        /// Path relative to the root.
+        path: PathBuf,
+        /// The expected content.
+        expected: Vec<u8>,
+        /// The actual content.
+        actual: Vec<u8>,
+    },
+    /// A generated file has different content, too large to be shown.
+    GeneratedMismatch {
+        /// Path relative to the root.
+        path: PathBuf,
+        /// The expected size in bytes.
+        expected_len: u64,
+        /// The actual size in bytes.
+        actual_len: u64,
+    },
+    /// An entry has different permissions than its expected settings.
+    SettingsMismatch {
+        /// Path relative to the root.
+        path: PathBuf,
+        /// Description of the mismatch.
+        message: String,
+    },
+}
+
+impl Difference {
+    /// Path of the entry, relative to the root.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Missing { path }
+            | Self::Extra { path }
+            | Self::KindMismatch { path, .. }
+            | Self::ContentMismatch { path, .. }
+            | Self::GeneratedMismatch { path, .. }
+            | Self::SettingsMismatch { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = display_path(self.path());
+        match self {
+            Self::Missing { .. } => write!(f, "- missing: `{path}`"),
+            Self::Extra { .. } => write!(f, "+ extra: `{path}`"),
+            Self::KindMismatch {
+                expected, actual, ..
+            } => write!(
+                f,
+                "~ kind mismatch: `{path}` expected {expected}, found {actual}"
+            ),
+            Self::GeneratedMismatch {
+                expected_len,
+                actual_len,
+                ..
+            } => write!(
+                f,
+                "~ content mismatch: `{path}`\n    generated content differs: expected {expected_len} bytes, found {actual_len} bytes"
+            ),
+            Self::SettingsMismatch { message, .. } => {
+                write!(f, "~ settings mismatch: `{path}` {message}")
+            }
+            Self::ContentMismatch {
+                expected, actual, ..
+            } => {
+                write!(f, "~ content mismatch: `{path}`")?;
+                match (std::str::from_utf8(expected), std::str::from_utf8(actual)) {
+                    (Ok(expected), Ok(actual)) => {
+                        let diff = similar::TextDiff::from_lines(expected, actual);
+                        let unified = diff.unified_diff().header("expected", "actual").to_string();
+                        for line in unified.lines() {
+                            write!(f, "\n    {line}")?;
+                        }
+                        Ok(())
+                    }
+                    _ => write!(
+                        f,
+                        "\n    binary content differs: expected {} bytes, found {} bytes",
+                        expected.len(),
+                        actual.len()
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// The differences between an expected [`TreeBuilder`] and a directory, as
+/// returned by [`compare`].
+///
+/// Its `Display` implementation renders a report of every difference, with a
+/// unified diff for text content.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diff {
+    /// Every difference found, sorted by path.
+    pub differences: Vec<Difference>,
+}
+
+impl Diff {
+    /// Whether the directory matches the expected entries.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("no differences");
+        }
+        write!(f, "{} difference(s):", self.differences.len())?;
+        for difference in &self.differences {
+            write!(f, "\n{difference}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the directory at `actual` against the entries of `expected`.
+///
+/// Ancestors of expected entries are expected to be directories. Settings are
+/// only compared when declared: the read-only flag, and on Unix the permission
+/// mode and executable bit. Timestamps are not compared. Variables are
+/// substituted with `{{root}}` being `actual`. Generated content is streamed
+/// and compared without being held in memory. Copied entries are only
+/// compared by kind, and the content of copied directories is not reported as
+/// extra.
+///
+/// # Examples
+///
+/// ```rust
+/// use tree_fs::{compare, CompareMode, TreeBuilder};
+/// let tree = TreeBuilder::default()
+///     .add_file("src/main.rs", "fn main() {}")
+///     .add_file("README.md", "# Demo")
+///     .create()
+///     .expect("create tree fs");
+///
+/// let expected = TreeBuilder::default().add_file("src/main.rs", "fn main() {}");
+/// let diff = compare(&expected, &tree.root, CompareMode::AtLeast).expect("compare");
+/// assert!(diff.is_empty(), "{diff}");
+///
+/// let diff = compare(&expected, &tree.root, CompareMode::Exact).expect("compare");
+/// assert_eq!(diff.to_string(), "1 difference(s):\n+ extra: `README.md`");
+/// ```
+///
+/// # Errors
+///
+/// Returns an error when the actual directory or one of its files cannot be
+/// read, or when `expected` uses an undefined variable in strict mode.
+pub fn compare<P: AsRef<Path>>(
+    expected: &TreeBuilder,
+    actual: P,
+    mode: CompareMode,
+) -> crate::Result<Diff> {
+    let root = actual.as_ref();
+
+    let entries = expected.rendered_entries(root)?;
+    let mut expected_entries: BTreeMap<PathBuf, Option<&Entry>> = BTreeMap::new();
+    let mut copies = Vec::new();
+    for entry in &entries {
+        let Some(path) = normalize(&entry.path) else {
+            continue;
+        };
+        if matches!(entry.kind, Kind::Copy { .. }) {
+            copies.push(path.clone());
+        }
+        for ancestor in path.ancestors().skip(1) {
+            if !ancestor.as_os_str().is_empty() {
+                expected_entries
+                    .entry(ancestor.to_path_buf())
+                    .or_insert(None);
+            }
+        }
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        let slot = expected_entries.entry(path).or_insert(None);
+        if slot.is_none() {
+            *slot = Some(entry);
+        }
+    }
+
+    let actual_paths: BTreeSet<PathBuf> = walk(root)
+        .context(Operation::Read, root)?
+        .into_iter()
+        .collect();
+
+    let mut differences = Vec::new();
+    for (path, entry) in &expected_entries {
+        if !actual_paths.contains(path) {
+            differences.push(Difference::Missing { path: path.clone() });
+            continue;
+        }
+        let full_path = root.join(path);
+        if let Some(entry) = entry {
+            compare_entry(expected, root, entry, path, &full_path, &mut differences)?;
+        } else {
+            let actual = actual_kind(&full_path).context(Operation::Read, &full_path)?;
+            if actual != "directory" {
+                differences.push(Difference::KindMismatch {
+                    path: path.clone(),
+                    expected: "directory",
+                    actual,
+                });
+            }
+        }
+    }
+
+    if mode == CompareMode::Exact {
+        differences.extend(
+            actual_paths
+                .into_iter()
+                .filter(|path| !expected_entries.contains_key(path))
+                .filter(|path| !copies.iter().any(|copy| path.starts_with(copy)))
+                // Content of unexpected directories is reported by the directory itself.
+                .filter(|path| {
+                    !path
+                        .ancestors()
+                        .skip(1)
+                        .any(|a| is_extra(a, &expected_entries))
+                })
+                .map(|path| Difference::Extra { path }),
+        );
+    }
+
+    differences.sort_by(|a, b| a.path().cmp(b.path()));
+    Ok(Diff { differences })
+}
+
+fn is_extra(ancestor: &Path, expected: &BTreeMap<PathBuf, Option<&Entry>>) -> bool {
+    !ancestor.as_os_str().is_empty() && !expected.contains_key(ancestor)
+}
+
+fn compare_entry(
+    builder: &TreeBuilder,
+    root: &Path,
+    entry: &Entry,
+    path: &Path,
+    full_path: &Path,
+    differences: &mut Vec<Difference>,
+) -> crate::Result<()> {
+    let expected_kind = expected_kind(&entry.kind).context(Operation::Read, &entry.path)?;
+    let actual = actual_kind(full_path).context(Operation::Read, full_path)?;
+    if expected_kind != actual {
+        differences.push(Difference::KindMismatch {
+            path: path.to_path_buf(),
+            expected: expected_kind,
+            actual,
+        });
+        return Ok(());
+    }
+
+    let expected_content = match &entry.kind {
+        Kind::EmptyFile => Some(Vec::new()),
+        Kind::TextFile { content } => Some(content.as_bytes().to_vec()),
+        Kind::BinaryFile { content } => Some(content.clone()),
+        Kind::Symlink { target } => {
+            let target = if builder.resolve_link_targets {
+                root.join(target)
+            } else {
+                target.clone()
+            };
+            Some(target.to_string_lossy().into_owned().into_bytes())
+        }
+        Kind::Generated { generator } => {
+            let file = std::fs::File::open(full_path).context(Operation::Read, full_path)?;
+            let actual_len = file.metadata().context(Operation::Read, full_path)?.len();
+            if !crate::generate::matches(generator, file).context(Operation::Read, full_path)? {
+                differences.push(Difference::GeneratedMismatch {
+                    path: path.to_path_buf(),
+                    expected_len: crate::generate::len(generator),
+                    actual_len,
+                });
+            }
+            None
+        }
+        Kind::Directory | Kind::HardLink { .. } | Kind::Copy { .. } => None,
+    };
+    if let Some(expected_content) = expected_content {
+        let actual_content = if matches!(entry.kind, Kind::Symlink { .. }) {
+            std::fs::read_link(full_path)
+                .map(|target| target.to_string_lossy().into_owned().into_bytes())
+        } else {
+            std::fs::read(full_path)
+        }
+        .context(Operation::Read, full_path)?;
+
+        if expected_content != actual_content {
+            differences.push(Difference::ContentMismatch {
+                path: path.to_path_buf(),
+                expected: expected_content,
+                actual: actual_content,
+            });
+        }
+    }
+
+    if let Some(settings) = &entry.settings {
+        if !matches!(entry.kind, Kind::Symlink { .. }) {
+            let metadata = std::fs::metadata(full_path).context(Operation::Read, full_path)?;
+            if let Some(message) = settings_mismatch(settings, &metadata) {
+                differences.push(Difference::SettingsMismatch {
+                    path: path.to_path_buf(),
+                    message,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn expected_kind(kind: &Kind) -> std::io::Result<&'static str> {
+    Ok(match kind {
+        Kind::Directory => "directory",
+        Kind::Symlink { .. } => "symlink",
+        Kind::EmptyFile
+        | Kind::TextFile { .. }
+        | Kind::BinaryFile { .. }
+        | Kind::HardLink { .. }
+        | Kind::Generated { .. } => "file",
+        Kind::Copy {
+            source,
+            follow_symlinks: true,
+        } => {
+            if std::fs::metadata(source)?.is_dir() {
+                "directory"
+            } else {
+                "file"
+            }
+        }
+        Kind::Copy { source, .. } => actual_kind(source)?,
+    })
+}
+
+fn actual_kind(path: &Path) -> std::io::Result<&'static str> {
+    let file_type = std::fs::symlink_metadata(path)?.file_type();
+    Ok(if file_type.is_symlink() {
+        "symlink"
+    } else if file_type.is_dir() {
+        "directory"
+    } else {
+        "file"
+    })
+}
+
+fn settings_mismatch(settings: &Settings, metadata: &std::fs::Metadata) -> Option<String> {
+    let readonly = metadata.permissions().readonly();
+    if settings.readonly && !readonly {
+        return Some("expected read-only, found writable".to_string());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let actual = metadata.permissions().mode() & 0o7777;
+        if let Some(mode) = settings.mode {
+            let mode = crate::tree::unix_mode(settings, mode);
+            if mode != actual {
+                return Some(format!("expected mode {mode:04o}, found {actual:04o}"));
+            }
+        } else if settings.executable && actual & 0o111 == 0 {
+            return Some(format!("expected executable, found mode {actual:04o}"));
+        }
+    }
+
+    None
+}
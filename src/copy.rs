@@ -0,0 +1,88 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Copies the file, directory or symlink at `source` to `dest`.
+///
+/// Directories are copied recursively in name order. Everything copied is
+/// left writable so later entries can still write over or into it, and the
+/// source permissions are pushed to `permissions` for the caller to apply
+/// once the tree is written. Those of a top-level directory are skipped when
+/// `keep_permissions` is false. Symlinks are recreated unless
+/// `follow_symlinks` is set, and symlinks already inside `dest` are replaced
+/// rather than written through. IO errors name the source path that failed.
+pub fn copy(
+    source: &Path,
+    dest: &Path,
+    follow_symlinks: bool,
+    keep_permissions: bool,
+    permissions: &mut Vec<(PathBuf, fs::Permissions)>,
+) -> io::Result<()> {
+    let metadata = if follow_symlinks {
+        fs::metadata(source)
+    } else {
+        fs::symlink_metadata(source)
+    }
+    .map_err(|e| with_source(&e, source))?;
+    let file_type = metadata.file_type();
+
+    if file_type.is_symlink() {
+        let target = fs::read_link(source).map_err(|e| with_source(&e, source))?;
+        if fs::symlink_metadata(dest).is_ok() {
+            fs::remove_file(dest)?;
+        }
+        return crate::builder::symlink(&target, dest);
+    }
+
+    if file_type.is_dir() {
+        fs::create_dir_all(dest)?;
+        let mut children = fs::read_dir(source)
+            .and_then(Iterator::collect::<io::Result<Vec<_>>>)
+            .map_err(|e| with_source(&e, source))?;
+        children.sort_by_key(fs::DirEntry::file_name);
+        for child in children {
+            let child_dest = dest.join(child.file_name());
+            if fs::symlink_metadata(&child_dest).is_ok_and(|metadata| metadata.is_symlink()) {
+                fs::remove_file(&child_dest)?;
+            }
+            copy(
+                &child.path(),
+                &child_dest,
+                follow_symlinks,
+                true,
+                permissions,
+            )?;
+        }
+        if keep_permissions {
+            permissions.push((dest.to_path_buf(), metadata.permissions()));
+        }
+        return Ok(());
+    }
+
+    // `fs::copy` carries the source permissions over right away.
+    fs::copy(source, dest).map_err(|e| with_source(&e, source))?;
+    if metadata.permissions().readonly() {
+        fs::set_permissions(dest, writable(metadata.permissions()))?;
+    }
+    permissions.push((dest.to_path_buf(), metadata.permissions()));
+    Ok(())
+}
+
+/// Grants the owner write access on top of `permissions`.
+fn writable(mut permissions: fs::Permissions) -> fs::Permissions {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.set_mode(permissions.mode() | 0o200);
+    }
+    #[cfg(not(unix))]
+    {
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+    }
+    permissions
+}
+
+fn with_source(error: &io::Error, source: &Path) -> io::Error {
+    io::Error::new(error.kind(), format!("`{}`: {error}", source.display()))
+}
@@ -0,0 +1,147 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+/// The operation that was being performed when an error occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Creating the root folder of the tree.
+    CreateRoot,
+    /// Creating a directory.
+    CreateDir,
+    /// Writing a file.
+    Write,
+    /// Creating a symbolic or hard link.
+    CreateLink,
+    /// Copying a file or directory into the tree.
+    Copy,
+    /// Setting the permissions of an entry.
+    SetPermissions,
+    /// Setting the timestamps of an entry.
+    SetTimes,
+    /// Reading a file.
+    Read,
+    /// Removing the tree.
+    Remove,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CreateRoot => "create root folder",
+            Self::CreateDir => "create directory",
+            Self::Write => "write file",
+            Self::CreateLink => "create link",
+            Self::Copy => "copy into",
+            Self::SetPermissions => "set permissions of",
+            Self::SetTimes => "set timestamps of",
+            Self::Read => "read",
+            Self::Remove => "remove",
+        })
+    }
+}
+
+/// Errors returned while building or loading a tree.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// An IO operation on an entry failed.
+    #[error("failed to {operation} `{}`: {source}", path.display())]
+    Io {
+        /// Path of the entry as declared, or of the root folder.
+        path: PathBuf,
+        /// The operation that failed.
+        operation: Operation,
+        /// The underlying IO error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// An entry would be written outside the root folder.
+    #[error("unsafe entry path `{}`: {reason}", path.display())]
+    UnsafePath {
+        /// Path of the entry as declared.
+        path: PathBuf,
+        /// Why the path was rejected.
+        reason: String,
+    },
+    /// A glob pattern is invalid.
+    #[error("invalid glob pattern `{pattern}`: {message}")]
+    InvalidGlob {
+        /// The pattern as given.
+        pattern: String,
+        /// Why the pattern is invalid.
+        message: String,
+    },
+    /// The file extension does not name a known spec format.
+    #[error(
+        "unknown spec format for `{}`, expected a .yaml, .yml, .json or .toml extension",
+        path.display()
+    )]
+    UnknownFormat {
+        /// Path of the spec file.
+        path: PathBuf,
+    },
+    /// The spec format is known but its cargo feature is not enabled.
+    #[error(
+        "cannot load `{}`: enable the `{feature}` feature of tree-fs to read this format",
+        path.display()
+    )]
+    FormatDisabled {
+        /// Path of the spec file.
+        path: PathBuf,
+        /// The cargo feature that provides the format.
+        feature: &'static str,
+    },
+    /// A template placeholder names a variable that is not defined, in strict
+    /// mode.
+    #[error("undefined variable `{name}` in entry `{}`", path.display())]
+    UndefinedVariable {
+        /// Path of the entry as declared.
+        path: PathBuf,
+        /// Name of the variable.
+        name: String,
+    },
+    /// Entries of the tree conflict with each other.
+    #[error("conflicting entries:{}", list_conflicts(.0))]
+    Conflicts(Vec<crate::Conflict>),
+    /// The YAML content could not be parsed.
+    #[cfg(feature = "yaml")]
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+    /// The JSON content could not be parsed.
+    #[cfg(feature = "json")]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The TOML content could not be parsed.
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    Toml(#[from] ::toml::de::Error),
+}
+
+fn list_conflicts(conflicts: &[crate::Conflict]) -> String {
+    use std::fmt::Write;
+
+    conflicts.iter().fold(String::new(), |mut list, conflict| {
+        let _ = write!(list, "\n  - {conflict}");
+        list
+    })
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Attaches the failing operation and entry path to IO errors.
+pub trait ResultExt<T> {
+    fn context(self, operation: Operation, path: &Path) -> Result<T>;
+}
+
+impl<T> ResultExt<T> for std::io::Result<T> {
+    fn context(self, operation: Operation, path: &Path) -> Result<T> {
+        self.map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            operation,
+            source,
+        })
+    }
+}
@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+/// Creates a file tree from a spec file, picking the format from its extension.
+///
+/// `.yaml` and `.yml` files are read as YAML, `.json` files as JSON and
+/// `.toml` files as TOML. Each format requires the cargo feature of the same
+/// name.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// let tree = tree_fs::from_file("tests/fixtures/tree.yaml").expect("create tree fs");
+/// ```
+///
+/// # Errors
+///
+/// Returns [`Error::UnknownFormat`] for other extensions,
+/// [`Error::FormatDisabled`] when the format's feature is not enabled, and
+/// otherwise the errors of the format's own loader.
+pub fn from_file<P: AsRef<Path>>(path: P) -> Result<crate::Tree> {
+    crate::TreeBuilder::from_file(path)?.create()
+}
+
+impl crate::TreeBuilder {
+    /// Parses a spec file into a builder without creating the tree, picking
+    /// the format from its extension like [`from_file`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`from_file`], except those of creating the
+    /// tree.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("yaml" | "yml") => read_yaml(path),
+            Some("json") => read_json(path),
+            Some("toml") => read_toml(path),
+            _ => Err(Error::UnknownFormat {
+                path: path.to_path_buf(),
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn read_yaml(path: &Path) -> Result<crate::TreeBuilder> {
+    crate::TreeBuilder::from_yaml_file(path)
+}
+
+#[cfg(not(feature = "yaml"))]
+fn read_yaml(path: &Path) -> Result<crate::TreeBuilder> {
+    Err(disabled(path, "yaml"))
+}
+
+#[cfg(feature = "json")]
+fn read_json(path: &Path) -> Result<crate::TreeBuilder> {
+    crate::TreeBuilder::from_json_file(path)
+}
+
+#[cfg(not(feature = "json"))]
+fn read_json(path: &Path) -> Result<crate::TreeBuilder> {
+    Err(disabled(path, "json"))
+}
+
+#[cfg(feature = "toml")]
+fn read_toml(path: &Path) -> Result<crate::TreeBuilder> {
+    crate::TreeBuilder::from_toml_file(path)
+}
+
+#[cfg(not(feature = "toml"))]
+fn read_toml(path: &Path) -> Result<crate::TreeBuilder> {
+    Err(disabled(path, "toml"))
+}
+
+#[cfg(not(all(feature = "yaml", feature = "json", feature = "toml")))]
+fn disabled(path: &Path, feature: &'static str) -> Error {
+    Error::FormatDisabled {
+        path: path.to_path_buf(),
+        feature,
+    }
+}
@@ -0,0 +1,161 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+use crate::Generator;
+
+/// Size of the chunks generated content is written in.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Lines cycled through by [`Generator::Lorem`].
+const LOREM: &[&str] = &[
+    "Lorem ipsum dolor sit amet, consectetur adipiscing elit.",
+    "Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.",
+    "Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris.",
+    "Nisi ut aliquip ex ea commodo consequat.",
+    "Duis aute irure dolor in reprehenderit in voluptate velit esse.",
+    "Cillum dolore eu fugiat nulla pariatur.",
+    "Excepteur sint occaecat cupidatat non proident.",
+    "Sunt in culpa qui officia deserunt mollit anim id est laborum.",
+];
+
+/// Writes the content of `generator` to `file`.
+pub fn write_file(generator: &Generator, file: File) -> io::Result<()> {
+    match generator {
+        Generator::Sparse { size } => file.set_len(*size),
+        _ => write_to(generator, file),
+    }
+}
+
+/// Whether `file` holds exactly the content of `generator`, streamed and
+/// compared chunk by chunk.
+pub fn matches(generator: &Generator, file: File) -> io::Result<bool> {
+    if file.metadata()?.len() != len(generator) {
+        return Ok(false);
+    }
+    let mut matcher = Matcher {
+        reader: BufReader::with_capacity(CHUNK_SIZE, file),
+        buf: Vec::new(),
+        matches: true,
+    };
+    match write_to(generator, &mut matcher) {
+        Ok(()) => Ok(matcher.matches),
+        // The matcher stops the generator at the first difference.
+        Err(_) if !matcher.matches => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Size of the content of `generator` in bytes.
+pub fn len(generator: &Generator) -> u64 {
+    match generator {
+        Generator::Repeat { size, .. }
+        | Generator::Random { size, .. }
+        | Generator::Sparse { size } => *size,
+        Generator::Lorem { lines } => {
+            let line_len = |line: &&str| line.len() as u64 + 1;
+            let cycle = LOREM.len() as u64;
+            let rest = usize::try_from(lines % cycle).unwrap_or_default();
+            lines / cycle * LOREM.iter().map(line_len).sum::<u64>()
+                + LOREM[..rest].iter().map(line_len).sum::<u64>()
+        }
+    }
+}
+
+/// Writes the content of `generator` to `writer`, sparse files as zeros.
+fn write_to(generator: &Generator, writer: impl Write) -> io::Result<()> {
+    match generator {
+        Generator::Repeat { pattern, size } => {
+            if pattern.is_empty() && *size > 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "cannot repeat an empty pattern",
+                ));
+            }
+            let pattern = pattern.as_bytes();
+            let chunk: Vec<u8> = pattern
+                .iter()
+                .copied()
+                .cycle()
+                .take(CHUNK_SIZE.div_ceil(pattern.len().max(1)) * pattern.len())
+                .collect();
+            write_chunks(writer, *size, |buf| buf.extend_from_slice(&chunk))
+        }
+        Generator::Random { size, seed } => {
+            let mut rng = SplitMix64(*seed);
+            write_chunks(writer, *size, |buf| {
+                while buf.len() < CHUNK_SIZE {
+                    buf.extend_from_slice(&rng.next().to_le_bytes());
+                }
+            })
+        }
+        Generator::Lorem { lines } => {
+            let mut writer = BufWriter::with_capacity(CHUNK_SIZE, writer);
+            for (_, line) in (0..*lines).zip(LOREM.iter().cycle()) {
+                writer.write_all(line.as_bytes())?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush()
+        }
+        Generator::Sparse { size } => write_chunks(writer, *size, |buf| buf.resize(CHUNK_SIZE, 0)),
+    }
+}
+
+/// Compares the bytes written to it with those read from `reader`, failing
+/// at the first difference.
+struct Matcher<R> {
+    reader: R,
+    buf: Vec<u8>,
+    matches: bool,
+}
+
+impl<R: Read> Write for Matcher<R> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.resize(data.len(), 0);
+        let read = self.reader.read_exact(&mut self.buf);
+        if read.is_err() || self.buf != data {
+            self.matches = false;
+            return Err(io::Error::other("generated content differs"));
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes `size` bytes to `writer`, refilling the buffer with `refill`.
+///
+/// Each refill starts from an empty buffer and must add at least one byte;
+/// bytes past `size` are discarded.
+fn write_chunks(
+    mut writer: impl Write,
+    size: u64,
+    mut refill: impl FnMut(&mut Vec<u8>),
+) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(CHUNK_SIZE + 8);
+    let mut remaining = size;
+    while remaining > 0 {
+        buf.clear();
+        refill(&mut buf);
+        let len =
+            usize::try_from(remaining).map_or(buf.len(), |remaining| remaining.min(buf.len()));
+        writer.write_all(&buf[..len])?;
+        remaining -= len as u64;
+    }
+    Ok(())
+}
+
+/// The `SplitMix64` generator, small and stable so seeded content never
+/// changes between releases.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    const fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE5_E4B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
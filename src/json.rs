@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use crate::error::{Operation, Result, ResultExt};
+
+/// Creates a file tree based on the content of a JSON file.
+///
+/// The file uses the same schema as the YAML format.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed, or if the tree
+/// cannot be created.
+pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<crate::Tree> {
+    crate::TreeBuilder::from_json_file(path)?.create()
+}
+
+/// Creates a file tree based on a JSON-formatted string.
+///
+/// # Examples
+///
+/// ```rust
+/// let tree = tree_fs::from_json_str(
+///     r#"{ "entries": [{ "path": "config.json", "type": "text_file", "content": "{}" }] }"#,
+/// )
+/// .expect("create tree fs");
+/// assert!(tree.root.join("config.json").exists());
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the content cannot be parsed or the tree cannot be
+/// created.
+pub fn from_json_str(content: &str) -> Result<crate::Tree> {
+    crate::TreeBuilder::from_json_str(content)?.create()
+}
+
+impl crate::TreeBuilder {
+    /// Parses a JSON string into a builder without creating the tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content cannot be parsed.
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        Ok(serde_json::from_str(content)?)
+    }
+
+    /// Parses a JSON file into a builder without creating the tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let f = std::fs::File::open(path).context(Operation::Read, path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(f))?)
+    }
+}
@@ -3,10 +3,124 @@
 #[cfg(feature = "yaml")]
 mod yaml;
 #[cfg(feature = "yaml")]
-pub use yaml::{from_yaml_file, from_yaml_str};
+pub use yaml::{
+    assert_snapshot, assert_snapshot_with, from_yaml_file, from_yaml_str, UPDATE_ENV_VAR,
+};
+
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "json")]
+pub use json::{from_json_file, from_json_str};
+
+#[cfg(feature = "toml")]
+mod toml;
+#[cfg(feature = "toml")]
+pub use crate::toml::{from_toml_file, from_toml_str};
+
+mod macros;
+
+#[cfg(all(feature = "macros", feature = "yaml"))]
+pub use tree_fs_macros::include_yaml;
+
+/// `include_yaml!` parses specs with the same model as `from_yaml_str`, and
+/// runs the path and conflict checks that need no file system, so a spec it
+/// would reject does not compile:
+///
+/// ```compile_fail
+/// let _ = tree_fs::include_yaml!("tests/fixtures/invalid/mode.yaml");
+/// ```
+///
+/// ```compile_fail
+/// let _ = tree_fs::include_yaml!("tests/fixtures/invalid/generator.yaml");
+/// ```
+///
+/// ```compile_fail
+/// let _ = tree_fs::include_yaml!("tests/fixtures/invalid/base64.yaml");
+/// ```
+///
+/// ```compile_fail
+/// let _ = tree_fs::include_yaml!("tests/fixtures/invalid/escape.yaml");
+/// ```
+///
+/// ```compile_fail
+/// let _ = tree_fs::include_yaml!("tests/fixtures/invalid/ancestor.yaml");
+/// ```
+///
+/// ```
+/// let _ = tree_fs::include_yaml!("tests/fixtures/tree.yaml");
+/// ```
+#[cfg(all(doctest, feature = "macros", feature = "yaml"))]
+pub struct IncludeYamlRejectsInvalidSpecs;
+#[cfg(feature = "macros")]
+pub use tree_fs_macros::test;
+
+/// Support code for the `tree_fs::test` attribute, not part of the public API.
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    /// Creates the tree of a test, keeping it when the test fails if
+    /// `keep_on_failure` is set and the builder does not already keep it.
+    #[must_use]
+    pub fn create(builder: crate::TreeBuilder, keep_on_failure: bool) -> crate::Tree {
+        let builder = if keep_on_failure && builder.drop == crate::DropPolicy::Always {
+            builder.drop_policy(crate::DropPolicy::OnSuccess)
+        } else {
+            builder
+        };
+        builder
+            .create()
+            .unwrap_or_else(|e| panic!("failed to create test tree: {e}"))
+    }
+
+    /// Loads a YAML spec relative to the test crate's manifest folder.
+    #[cfg(feature = "yaml")]
+    #[must_use]
+    pub fn from_yaml_file(manifest_dir: &str, path: &str) -> crate::TreeBuilder {
+        let path = std::path::Path::new(manifest_dir).join(path);
+        crate::TreeBuilder::from_yaml_file(&path)
+            .unwrap_or_else(|e| panic!("failed to load `{}`: {e}", path.display()))
+    }
+
+    /// Loads a YAML spec embedded by `include_yaml!`.
+    #[cfg(feature = "yaml")]
+    #[must_use]
+    pub fn from_yaml_str(content: &str) -> crate::TreeBuilder {
+        crate::TreeBuilder::from_yaml_str(content)
+            .unwrap_or_else(|e| panic!("failed to load embedded spec: {e}"))
+    }
+}
+
+mod generate;
+
+mod format;
+pub use format::from_file;
+
+mod error;
+pub use error::{Error, Operation, Result};
+
+#[cfg(feature = "spec")]
+mod spec;
+
+mod assert;
+pub use assert::{PathAssert, TreeAssert};
+
+mod capture;
+pub use capture::CaptureOptions;
+
+mod compare;
+mod copy;
+pub use compare::{compare, CompareMode, Diff, Difference};
 
 mod builder;
 pub use builder::TreeBuilder;
 
+mod template;
+
 mod tree;
-pub use tree::{Entry, Kind, Settings, Tree};
+pub use tree::{Tree, KEEP_ENV_VAR};
+pub use tree_fs_spec::{DropPolicy, Entry, Generator, Kind, Settings, Timestamp};
+
+mod validate;
+pub use tree_fs_spec::Conflict;
+
+mod walk;
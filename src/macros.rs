@@ -0,0 +1,129 @@
+/// Builds a [`TreeBuilder`](crate::TreeBuilder) from a nested description.
+///
+/// Each entry is a path literal followed by `=>` and one of:
+///
+/// - `{ ... }`: a directory with the nested entries,
+/// - `dir`: an empty directory,
+/// - `empty`: an empty file,
+/// - any other expression: a text file with that content.
+///
+/// [`Settings`](crate::Settings) go in brackets after the path, e.g.
+/// `[readonly]`, `[mode = 0o600]` or `[executable, modified = time]`. A flag
+/// without a value is set to `true`.
+///
+/// A level whose values are all single tokens, such as literals, variables,
+/// `dir`, `empty`, `{ ... }` blocks or parenthesized expressions, expands
+/// without recursing per entry. Longer expressions like `format!(..)` are
+/// expanded one entry at a time and count towards the recursion limit, so
+/// wrap them in parentheses in very wide levels.
+///
+/// # Examples
+///
+/// ```rust
+/// let tree = tree_fs::tree! {
+///     "src" => {
+///         "main.rs" => "fn main() {}",
+///     },
+///     "config.ini" [readonly] => "[general]",
+///     "empty.txt" => empty,
+///     "bin" => dir,
+/// }
+/// .create()
+/// .expect("create tree fs");
+///
+/// assert!(tree.root.join("src/main.rs").exists());
+/// ```
+///
+/// Malformed entries fail to compile:
+///
+/// ```compile_fail
+/// let builder = tree_fs::tree! { "main.rs" => };
+/// ```
+#[macro_export]
+macro_rules! tree {
+    // Values that are a single token tree expand side by side, so a level
+    // of any width costs one recursion step.
+    (@entries $builder:ident $parent:ident
+        $($name:literal $([$($settings:tt)*])? => $value:tt),* $(,)?
+    ) => {{
+        $(let $builder = $crate::tree!(@entry $builder $parent $name [$($($settings)*)?] $value);)*
+        $builder
+    }};
+    // Otherwise the first entry is expanded on its own, since an expression
+    // spanning several tokens can only be told apart from the next entry by
+    // matching it as a whole.
+    (@entries $builder:ident $parent:ident
+        $name:literal $([$($settings:tt)*])? => $value:tt $(, $($rest:tt)*)?
+    ) => {{
+        let $builder = $crate::tree!(@entry $builder $parent $name [$($($settings)*)?] $value);
+        $crate::tree!(@entries $builder $parent $($($rest)*)?)
+    }};
+    (@entries $builder:ident $parent:ident
+        $name:literal $([$($settings:tt)*])? => $content:expr $(, $($rest:tt)*)?
+    ) => {{
+        let $builder = $crate::tree!(@entry $builder $parent $name [$($($settings)*)?] $content);
+        $crate::tree!(@entries $builder $parent $($($rest)*)?)
+    }};
+    (@entries $builder:ident $parent:ident $($invalid:tt)*) => {
+        ::std::compile_error!(::std::concat!(
+            "invalid tree! entry, expected `\"path\" => value`: ",
+            ::std::stringify!($($invalid)*)
+        ))
+    };
+
+    (@entry $builder:ident $parent:ident $name:literal [$($settings:tt)*] { $($children:tt)* }) => {{
+        let path = $parent.join($name);
+        let $builder = $crate::tree!(@add $builder path Directory [$($settings)*]);
+        $crate::tree!(@entries $builder path $($children)*)
+    }};
+    (@entry $builder:ident $parent:ident $name:literal [$($settings:tt)*] dir) => {{
+        let path = $parent.join($name);
+        $crate::tree!(@add $builder path Directory [$($settings)*])
+    }};
+    (@entry $builder:ident $parent:ident $name:literal [$($settings:tt)*] empty) => {{
+        let path = $parent.join($name);
+        $crate::tree!(@add $builder path EmptyFile [$($settings)*])
+    }};
+    (@entry $builder:ident $parent:ident $name:literal [$($settings:tt)*] $content:expr) => {{
+        let path = $parent.join($name);
+        $crate::tree!(@add $builder path TextFile {
+            content: ::std::string::String::from($content),
+        } [$($settings)*])
+    }};
+
+    (@add $builder:ident $path:ident $kind:ident $({ $($fields:tt)* })? [$($settings:tt)*]) => {
+        $builder.add_entry($crate::Entry {
+            path: $path.clone(),
+            kind: $crate::Kind::$kind $({ $($fields)* })?,
+            settings: $crate::tree!(@settings $($settings)*),
+        })
+    };
+
+    (@settings) => {
+        ::std::option::Option::None
+    };
+    (@settings $($flag:ident $(= $value:expr)?),+ $(,)?) => {
+        ::std::option::Option::Some(
+            $crate::Settings::default() $(.$flag($crate::tree!(@value $($value)?)))+
+        )
+    };
+    (@settings $($invalid:tt)*) => {
+        ::std::compile_error!(::std::concat!(
+            "invalid tree! settings, expected `[flag, flag = value]`: ",
+            ::std::stringify!($($invalid)*)
+        ))
+    };
+
+    (@value) => {
+        true
+    };
+    (@value $value:expr) => {
+        $value
+    };
+
+    ($($entries:tt)*) => {{
+        let builder = $crate::TreeBuilder::default();
+        let root = ::std::path::PathBuf::new();
+        $crate::tree!(@entries builder root $($entries)*)
+    }};
+}
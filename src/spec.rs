@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use serde::Serialize;
+use tree_fs_spec::{Nested, Scalar, TreeSpec};
+
+use crate::{DropPolicy, TreeBuilder};
+
+impl From<TreeSpec> for TreeBuilder {
+    fn from(spec: TreeSpec) -> Self {
+        let builder = Self::default()
+            .override_file(spec.override_file)
+            .drop_policy(spec.drop_policy.unwrap_or_else(|| spec.drop.into()))
+            .resolve_link_targets(spec.resolve_link_targets)
+            .strict_vars(spec.strict_vars)
+            .templating(spec.templating);
+        let mut builder = match spec.root {
+            Some(root) => builder.root_folder(root),
+            None => builder,
+        };
+        builder.vars.values = spec
+            .vars
+            .into_iter()
+            .map(|(name, Scalar(value))| (name, value))
+            .collect();
+        spec.tree.expand(Path::new(""), &mut builder.entries);
+        builder.entries.extend(spec.entries);
+        builder
+    }
+}
+
+impl From<&TreeBuilder> for TreeSpec {
+    fn from(builder: &TreeBuilder) -> Self {
+        Self {
+            // Temporary roots are picked again whenever the spec is loaded.
+            root: builder.temp_root().is_none().then(|| builder.root.clone()),
+            override_file: builder.override_file,
+            tree: Nested::default(),
+            entries: builder.entries.clone(),
+            drop: builder.drop != DropPolicy::Never,
+            drop_policy: (builder.drop == DropPolicy::OnSuccess).then_some(DropPolicy::OnSuccess),
+            resolve_link_targets: builder.resolve_link_targets,
+            vars: builder
+                .vars
+                .values
+                .iter()
+                .map(|(name, value)| (name.clone(), Scalar(value.clone())))
+                .collect(),
+            strict_vars: builder.vars.strict,
+            templating: builder.vars.enabled,
+        }
+    }
+}
+
+impl Serialize for TreeBuilder {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TreeSpec::from(self).serialize(serializer)
+    }
+}
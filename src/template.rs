@@ -0,0 +1,181 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::{Conflict, Entry, Error, Kind, Result};
+
+/// Name of the variable holding the root folder of the tree.
+pub const ROOT_VAR: &str = "root";
+
+/// Variables substituted in entry paths and text content.
+#[derive(Debug, Clone)]
+pub struct Vars {
+    /// Values by variable name.
+    pub values: BTreeMap<String, String>,
+    /// Whether placeholders naming an undefined variable are an error.
+    pub strict: bool,
+    /// Whether placeholders are substituted at all.
+    pub enabled: bool,
+}
+
+impl Default for Vars {
+    fn default() -> Self {
+        Self {
+            values: BTreeMap::new(),
+            strict: false,
+            enabled: true,
+        }
+    }
+}
+
+/// Replaces `{{ name }}` placeholders in `template` with the value `lookup`
+/// returns for `name`.
+///
+/// Unknown names are left untouched, or returned as the error when `strict`.
+/// Braces around anything that is not a variable name, such as
+/// `{{ .Values }}`, are never treated as a placeholder.
+fn render<'a, 'v>(
+    template: &'a str,
+    lookup: impl Fn(&str) -> Option<&'v str>,
+    strict: bool,
+) -> std::result::Result<Cow<'a, str>, String> {
+    let mut rendered = String::new();
+    let mut rest = template;
+    let mut changed = false;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = after[..end].trim();
+        if is_name(name) {
+            if let Some(value) = lookup(name) {
+                rendered.push_str(&rest[..start]);
+                rendered.push_str(value);
+                rest = &after[end + 2..];
+                changed = true;
+                continue;
+            }
+            if strict {
+                return Err(name.to_string());
+            }
+        }
+        rendered.push_str(&rest[..start + 2]);
+        rest = after;
+    }
+
+    if !changed {
+        return Ok(Cow::Borrowed(template));
+    }
+    rendered.push_str(rest);
+    Ok(Cow::Owned(rendered))
+}
+
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+impl crate::TreeBuilder {
+    /// Returns the entries with variables substituted in their paths and text
+    /// content, `root` being the value of `{{root}}`.
+    pub(crate) fn rendered_entries(&self, root: &Path) -> Result<Vec<Entry>> {
+        if !self.vars.enabled {
+            return Ok(self.entries.clone());
+        }
+        let root = root.to_string_lossy();
+        let lookup = |name: &str| {
+            if name == ROOT_VAR {
+                Some(root.as_ref())
+            } else {
+                self.vars.values.get(name).map(String::as_str)
+            }
+        };
+        let undefined = |entry: &Entry, name| Error::UndefinedVariable {
+            path: entry.path.clone(),
+            name,
+        };
+
+        self.entries
+            .iter()
+            .map(|entry| {
+                let mut rendered = entry.clone();
+                if let Some(path) = entry.path.to_str() {
+                    rendered.path = PathBuf::from(
+                        render(path, lookup, self.vars.strict)
+                            .map_err(|name| undefined(entry, name))?
+                            .as_ref(),
+                    );
+                }
+                if let Kind::TextFile { content } = &mut rendered.kind {
+                    if let Cow::Owned(text) = render(content, lookup, self.vars.strict)
+                        .map_err(|name| undefined(entry, name))?
+                    {
+                        *content = text;
+                    }
+                }
+                Ok(rendered)
+            })
+            .collect()
+    }
+
+    /// Reports `error`, raised for the rendered `entries`, against the paths
+    /// as declared. The rendered form of an unsafe path is kept in its reason.
+    pub(crate) fn declared_error(&self, entries: &[Entry], error: Error) -> Error {
+        let declared = |path: PathBuf| {
+            entries
+                .iter()
+                .zip(&self.entries)
+                .find(|(rendered, _)| rendered.path == path)
+                .map_or(path, |(_, declared)| declared.path.clone())
+        };
+
+        match error {
+            Error::UnsafePath { path, reason } => {
+                let declared_path = declared(path.clone());
+                let reason = if declared_path == path {
+                    reason
+                } else {
+                    format!("{reason} (rendered as `{}`)", path.display())
+                };
+                Error::UnsafePath {
+                    path: declared_path,
+                    reason,
+                }
+            }
+            Error::Io {
+                path,
+                operation,
+                source,
+            } => Error::Io {
+                path: declared(path),
+                operation,
+                source,
+            },
+            Error::Conflicts(conflicts) => Error::Conflicts(
+                conflicts
+                    .into_iter()
+                    .map(|conflict| match conflict {
+                        Conflict::Duplicate { path } => Conflict::Duplicate {
+                            path: declared(path),
+                        },
+                        Conflict::KindMismatch { path } => Conflict::KindMismatch {
+                            path: declared(path),
+                        },
+                        Conflict::Ancestor { path, descendant } => Conflict::Ancestor {
+                            path: declared(path),
+                            descendant: declared(descendant),
+                        },
+                        Conflict::Root { path } => Conflict::Root {
+                            path: declared(path),
+                        },
+                    })
+                    .collect(),
+            ),
+            error => error,
+        }
+    }
+}
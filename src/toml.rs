@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use crate::error::{Operation, Result, ResultExt};
+
+/// Creates a file tree based on the content of a TOML file.
+///
+/// The file uses the same schema as the YAML format, with entries written as
+/// an array of tables (`[[entries]]`).
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed, or if the tree
+/// cannot be created.
+pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<crate::Tree> {
+    crate::TreeBuilder::from_toml_file(path)?.create()
+}
+
+/// Creates a file tree based on a TOML-formatted string.
+///
+/// # Examples
+///
+/// ```rust
+/// let tree = tree_fs::from_toml_str(
+///     r#"
+/// [[entries]]
+/// path = "Cargo.toml"
+/// type = "text_file"
+/// content = "[package]"
+/// "#,
+/// )
+/// .expect("create tree fs");
+/// assert!(tree.root.join("Cargo.toml").exists());
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the content cannot be parsed or the tree cannot be
+/// created.
+pub fn from_toml_str(content: &str) -> Result<crate::Tree> {
+    crate::TreeBuilder::from_toml_str(content)?.create()
+}
+
+impl crate::TreeBuilder {
+    /// Parses a TOML string into a builder without creating the tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content cannot be parsed.
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        Ok(::toml::from_str(content)?)
+    }
+
+    /// Parses a TOML file into a builder without creating the tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).context(Operation::Read, path)?;
+        Self::from_toml_str(&content)
+    }
+}
@@ -1,104 +1,242 @@
 use rand::{distr::Alphanumeric, rng, Rng};
 
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-#[cfg(feature = "yaml")]
-use serde::Deserialize;
-#[cfg(feature = "yaml")]
-use serde::Serialize;
+use crate::error::{Operation, ResultExt};
+use crate::{DropPolicy, Settings};
+
+/// Environment variable that, when set to anything but `0` or `false`,
+/// keeps every tree on drop regardless of its [`DropPolicy`].
+pub const KEEP_ENV_VAR: &str = "TREE_FS_KEEP";
 
 /// Represents a file tree structure
 #[derive(Debug)]
 pub struct Tree {
     /// Root folder where the tree will be created.
     pub root: PathBuf,
-    /// Whether to automatically delete the temporary folder when dropped
-    pub(crate) drop: bool,
+    /// When to automatically delete the root folder when dropped
+    pub(crate) drop: DropPolicy,
+}
+
+impl Tree {
+    /// Starts assertions on the content of the tree.
+    #[must_use]
+    pub fn assert(&self) -> crate::TreeAssert<'_> {
+        crate::TreeAssert::new(self.root.as_path())
+    }
+
+    /// Deletes the root folder now, regardless of the drop policy, and reports
+    /// any cleanup error instead of ignoring it like `drop` does.
+    ///
+    /// Permissions are restored first, so read-only files and directories
+    /// are removed as well. The tree is still kept when [`KEEP_ENV_VAR`] is
+    /// set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the root folder cannot be removed.
+    pub fn close(mut self) -> crate::Result<()> {
+        self.drop = DropPolicy::Never;
+        if keep_requested() {
+            eprintln!("tree-fs: keeping tree at {}", self.root.display());
+            return Ok(());
+        }
+        remove_tree(&self.root).context(Operation::Remove, &self.root)
+    }
 }
 
 impl Drop for Tree {
     fn drop(&mut self) {
-        if self.drop {
-            let _ = std::fs::remove_dir_all(&self.root);
+        let keep = match self.drop {
+            DropPolicy::Never => return,
+            DropPolicy::Always => false,
+            DropPolicy::OnSuccess => std::thread::panicking(),
+        };
+
+        if keep || keep_requested() {
+            eprintln!("tree-fs: keeping tree at {}", self.root.display());
+        } else {
+            let _ = remove_tree(&self.root);
         }
     }
 }
 
-/// Settings for entries in the tree.
-/// Currently supports read-only flag, but can be extended with additional settings.
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "yaml", derive(Deserialize, Serialize))]
-#[derive(Default)]
-pub struct Settings {
-    /// Whether the file is read-only.
-    #[cfg_attr(
-        feature = "yaml",
-        serde(default, skip_serializing_if = "std::ops::Not::not")
-    )]
-    pub readonly: bool,
-    // Future settings could be added here:
-    // pub timestamp: Option<SystemTime>,
-    // pub owner: Option<String>,
-    // etc.
+/// Removes `root` recursively, restoring permissions that would prevent it.
+pub fn remove_tree(root: &Path) -> std::io::Result<()> {
+    if std::fs::remove_dir_all(root).is_ok() || !root.exists() {
+        return Ok(());
+    }
+    make_removable(root)?;
+    std::fs::remove_dir_all(root)
 }
 
-// Builder pattern for Settings
-impl Settings {
-    /// Creates a new Settings with default values.
-    #[must_use]
-    pub fn new() -> Self {
-        Self::default()
+/// Grants the owner write and traverse access to `path` and everything
+/// below it, without following symlinks.
+fn make_removable(path: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(path)?;
+    if metadata.file_type().is_symlink() {
+        return Ok(());
     }
 
-    /// Sets whether the file is read-only.
-    #[must_use]
-    pub const fn readonly(mut self, value: bool) -> Self {
-        self.readonly = value;
-        self
+    let mut permissions = metadata.permissions();
+    #[cfg(unix)]
+    if metadata.is_dir() {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.set_mode(permissions.mode() | 0o700);
+        std::fs::set_permissions(path, permissions)?;
     }
+    // Windows refuses to delete read-only files and directories.
+    #[cfg(not(unix))]
+    if permissions.readonly() {
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        std::fs::set_permissions(path, permissions)?;
+    }
+
+    if metadata.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            make_removable(&entry?.path())?;
+        }
+    }
+    Ok(())
 }
 
-/// Describes what kind of entry to create
-#[derive(Debug, Clone)]
-#[cfg_attr(feature = "yaml", derive(Deserialize))]
-#[cfg_attr(feature = "yaml", serde(tag = "type"))]
-pub enum Kind {
-    /// A directory
-    #[cfg_attr(feature = "yaml", serde(rename = "directory"))]
-    Directory,
-    /// An empty file
-    #[cfg_attr(feature = "yaml", serde(rename = "empty_file"))]
-    EmptyFile,
-    /// A file with text content
-    #[cfg_attr(feature = "yaml", serde(rename = "text_file"))]
-    TextFile { content: String },
+/// Whether [`KEEP_ENV_VAR`] asks to keep trees.
+fn keep_requested() -> bool {
+    env::var_os(KEEP_ENV_VAR)
+        .is_some_and(|value| !value.is_empty() && value != "0" && value != "false")
 }
 
-/// Represents an entry, file or directory, to be created.
-#[derive(Debug)]
-#[cfg_attr(feature = "yaml", derive(Deserialize))]
-pub struct Entry {
-    /// Path of the entry relative to the root folder.
-    pub path: PathBuf,
-    /// The kind of the entry
-    #[cfg_attr(feature = "yaml", serde(flatten))]
-    pub kind: Kind,
-    /// Optional settings for the entry
-    #[cfg_attr(
-        feature = "yaml",
-        serde(default, skip_serializing_if = "Option::is_none")
-    )]
-    pub settings: Option<Settings>,
+/// Sets the timestamps of `settings` on the entry at `path`, resolving
+/// relative timestamps against `now`.
+///
+/// Timestamps must be set before permissions, since a read-only entry may
+/// reject them otherwise. Timestamps that cannot be represented are an
+/// `InvalidInput` error.
+pub fn apply_times(settings: &Settings, path: &Path, now: SystemTime) -> std::io::Result<()> {
+    let resolve = |timestamp: crate::Timestamp| {
+        timestamp
+            .resolve(now)
+            .map(filetime::FileTime::from_system_time)
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "timestamp is out of range",
+                )
+            })
+    };
+    let modified = settings.modified.map(resolve).transpose()?;
+    let accessed = settings.accessed.map(resolve).transpose()?;
+
+    match (accessed, modified) {
+        (Some(accessed), Some(modified)) => filetime::set_file_times(path, accessed, modified),
+        (Some(accessed), None) => filetime::set_file_atime(path, accessed),
+        (None, Some(modified)) => filetime::set_file_mtime(path, modified),
+        (None, None) => Ok(()),
+    }
+}
+
+/// Applies the `executable` and `readonly` flags of `settings` on top of `mode`.
+#[cfg(unix)]
+pub const fn unix_mode(settings: &Settings, mut mode: u32) -> u32 {
+    if settings.executable {
+        mode |= (mode & 0o444) >> 2;
+    }
+    if settings.readonly {
+        mode &= !0o222;
+    }
+    mode
 }
 
-/// Creates a temporary directory with a random name
-pub fn temp_dir() -> PathBuf {
-    let random_string: String = rng()
-        .sample_iter(&Alphanumeric)
-        .take(5)
-        .map(char::from)
-        .collect();
+/// Sets the permissions of `settings` on the entry at `path`.
+pub fn apply_permissions(settings: &Settings, path: &Path) -> std::io::Result<()> {
+    if !settings.readonly && !settings.executable && settings.mode.is_none() {
+        return Ok(());
+    }
+
+    let mut permissions = std::fs::metadata(path)?.permissions();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = settings.mode.unwrap_or_else(|| permissions.mode() & 0o7777);
+        permissions.set_mode(unix_mode(settings, mode));
+    }
+
+    #[cfg(not(unix))]
+    {
+        if let Some(mode) = settings.mode {
+            permissions.set_readonly(mode & 0o200 == 0);
+        }
+        if settings.readonly {
+            permissions.set_readonly(true);
+        }
+    }
 
-    env::temp_dir().join(random_string)
+    std::fs::set_permissions(path, permissions)
+}
+
+/// Naming of a temporary root folder, allocated when the tree is created.
+#[derive(Debug, Clone, Default)]
+pub struct TempRoot {
+    /// Prefix of the folder name.
+    pub prefix: String,
+    /// Suffix of the folder name.
+    pub suffix: String,
+    /// Folder in which the root is created, the system temporary folder when `None`.
+    pub parent: Option<PathBuf>,
+    /// First candidate, as assigned to [`crate::TreeBuilder::root`].
+    pub first: PathBuf,
+}
+
+impl TempRoot {
+    /// Number of random characters in the folder name.
+    const RANDOM_LEN: usize = 12;
+    /// Number of names tried before giving up.
+    const ATTEMPTS: usize = 64;
+
+    /// Picks a random folder path, without creating it.
+    pub fn candidate(&self) -> PathBuf {
+        let random_string: String = rng()
+            .sample_iter(&Alphanumeric)
+            .take(Self::RANDOM_LEN)
+            .map(char::from)
+            .collect();
+        let parent = self.parent.clone().unwrap_or_else(env::temp_dir);
+
+        parent.join(format!("{}{random_string}{}", self.prefix, self.suffix))
+    }
+
+    /// Atomically creates a fresh root folder, starting with the first
+    /// candidate and picking new names while candidates already exist.
+    ///
+    /// On Unix the folder is only accessible by its owner (`0700`).
+    pub fn allocate(&self) -> std::io::Result<PathBuf> {
+        if let Some(parent) = self.first.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut candidate = self.first.clone();
+        for _ in 0..Self::ATTEMPTS {
+            let mut builder = std::fs::DirBuilder::new();
+            #[cfg(unix)]
+            std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+
+            match builder.create(&candidate) {
+                Ok(()) => return Ok(candidate),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    candidate = self.candidate();
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            "could not find an unused temporary folder name",
+        ))
+    }
 }
@@ -0,0 +1,222 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use tree_fs_spec::check_path;
+pub use tree_fs_spec::normalize;
+
+use crate::{Entry, Error, Kind, Result};
+
+/// Checks that no entry can be written outside `root`.
+///
+/// Rejects absolute paths, `..` escapes, hard links to files outside the root
+/// and paths that traverse a symlink, declared or already on disk, pointing
+/// outside the root. Hard link targets get the same symlink checks as entry
+/// paths.
+pub fn check_paths(root: &Path, entries: &[Entry], resolve_link_targets: bool) -> Result<()> {
+    let traversal = Traversal {
+        root,
+        symlinks: entries
+            .iter()
+            .filter_map(|entry| match &entry.kind {
+                Kind::Symlink { target } => Some((normalize(&entry.path)?, target.as_path())),
+                _ => None,
+            })
+            .collect(),
+        canonical_root: root.canonicalize().ok(),
+        resolve_link_targets,
+    };
+
+    for entry in entries {
+        let normalized =
+            check_path(&entry.path).map_err(|reason| unsafe_path(&entry.path, reason))?;
+
+        if let Kind::HardLink { target } = &entry.kind {
+            let normalized_target = if target_inside(root, Path::new(""), target) {
+                normalize(target.strip_prefix(root).unwrap_or(target))
+            } else {
+                None
+            };
+            let Some(normalized_target) = normalized_target else {
+                return Err(unsafe_path(
+                    &entry.path,
+                    format!(
+                        "hard link target `{}` is outside the root folder",
+                        target.display()
+                    ),
+                ));
+            };
+            traversal.check(&entry.path, &normalized_target, 0, "hard link target ")?;
+        }
+
+        // Links replace whatever is at their own path, everything else is
+        // written through it.
+        let is_link = matches!(entry.kind, Kind::Symlink { .. } | Kind::HardLink { .. });
+        traversal.check(&entry.path, &normalized, usize::from(is_link), "")?;
+    }
+
+    Ok(())
+}
+
+/// Checks, right before `entry` is written at `dest`, that it resolves inside
+/// `canonical_root`.
+///
+/// Copies may bring in symlinks that [`check_paths`] could not see, so the
+/// deepest existing ancestor of the destination, and of a hard link target,
+/// is resolved again.
+pub fn check_destination(
+    canonical_root: &Path,
+    root: &Path,
+    entry: &Entry,
+    dest: &Path,
+) -> Result<()> {
+    // Links replace whatever is at their own path.
+    let written = match &entry.kind {
+        Kind::Symlink { .. } | Kind::HardLink { .. } => dest.parent().unwrap_or(dest),
+        _ => dest,
+    };
+    check_resolved(canonical_root, &entry.path, written, "")?;
+
+    if let Kind::HardLink { target } = &entry.kind {
+        check_resolved(
+            canonical_root,
+            &entry.path,
+            &root.join(target),
+            "hard link target ",
+        )?;
+    }
+    Ok(())
+}
+
+fn check_resolved(canonical_root: &Path, path: &Path, on_disk: &Path, subject: &str) -> Result<()> {
+    let Some(existing) = on_disk
+        .ancestors()
+        .find(|ancestor| std::fs::symlink_metadata(ancestor).is_ok())
+    else {
+        return Ok(());
+    };
+    match existing.canonicalize() {
+        Ok(resolved) if resolved.starts_with(canonical_root) => Ok(()),
+        Ok(resolved) => Err(unsafe_path(
+            path,
+            format!(
+                "{subject}resolves to `{}` outside the root folder",
+                resolved.display()
+            ),
+        )),
+        Err(_) => Err(unsafe_path(
+            path,
+            format!(
+                "{subject}traverses dangling symlink `{}`",
+                existing.display()
+            ),
+        )),
+    }
+}
+
+/// Number of declared symlinks followed before a path is rejected, as Linux
+/// does for symlinks on disk.
+const MAX_SYMLINK_DEPTH: usize = 40;
+
+/// Symlinks, declared or on disk, that a path may be resolved through.
+struct Traversal<'a> {
+    root: &'a Path,
+    symlinks: HashMap<PathBuf, &'a Path>,
+    canonical_root: Option<PathBuf>,
+    resolve_link_targets: bool,
+}
+
+impl Traversal<'_> {
+    /// Checks every ancestor of `normalized`, except the last `skip`
+    /// components, for a symlink pointing outside the root. Declared symlinks
+    /// are followed, so chains of them are checked as a whole. Errors are
+    /// reported for `path`.
+    fn check(&self, path: &Path, normalized: &Path, skip: usize, subject: &str) -> Result<()> {
+        self.check_from(path, normalized, skip, subject, 0)
+    }
+
+    fn check_from(
+        &self,
+        path: &Path,
+        normalized: &Path,
+        skip: usize,
+        subject: &str,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > MAX_SYMLINK_DEPTH {
+            return Err(unsafe_path(
+                path,
+                format!("{subject}resolves through more than {MAX_SYMLINK_DEPTH} symlinks"),
+            ));
+        }
+
+        let checked = normalized.components().count().saturating_sub(skip);
+        let mut ancestor = PathBuf::new();
+        for component in normalized.components().take(checked) {
+            ancestor.push(component);
+
+            if let Some(target) = self.symlinks.get(&ancestor) {
+                let base = if self.resolve_link_targets || target.is_absolute() {
+                    Path::new("")
+                } else {
+                    ancestor.parent().unwrap_or_else(|| Path::new(""))
+                };
+                let resolved = if target_inside(self.root, base, target) {
+                    normalize(&base.join(target.strip_prefix(self.root).unwrap_or(target)))
+                } else {
+                    None
+                };
+                let Some(resolved) = resolved else {
+                    return Err(unsafe_path(
+                        path,
+                        format!(
+                            "{subject}traverses symlink `{}` which points outside the root folder",
+                            ancestor.display()
+                        ),
+                    ));
+                };
+                // Continue from where the link leads, which may be another link.
+                let rest = normalized.strip_prefix(&ancestor).unwrap_or(normalized);
+                return self.check_from(path, &resolved.join(rest), skip, subject, depth + 1);
+            }
+
+            let Some(canonical_root) = &self.canonical_root else {
+                continue;
+            };
+            let on_disk = self.root.join(&ancestor);
+            let is_symlink = std::fs::symlink_metadata(&on_disk)
+                .is_ok_and(|metadata| metadata.file_type().is_symlink());
+            if is_symlink
+                && !on_disk
+                    .canonicalize()
+                    .is_ok_and(|resolved| resolved.starts_with(canonical_root))
+            {
+                return Err(unsafe_path(
+                    path,
+                    format!(
+                        "{subject}traverses existing symlink `{}` which points outside the root folder",
+                        ancestor.display()
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `target`, relative to `base` inside `root`, stays inside `root`.
+fn target_inside(root: &Path, base: &Path, target: &Path) -> bool {
+    if target.is_absolute() {
+        target.starts_with(root) && normalize(target.strip_prefix(root).unwrap_or(target)).is_some()
+    } else {
+        normalize(&base.join(target)).is_some()
+    }
+}
+
+fn unsafe_path(path: &Path, reason: impl Into<String>) -> Error {
+    Error::UnsafePath {
+        path: path.to_path_buf(),
+        reason: reason.into(),
+    }
+}
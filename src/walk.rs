@@ -0,0 +1,67 @@
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// Options used to match glob patterns against paths rendered by
+/// [`display_path`]: `*` does not cross directories while `**` does.
+pub const GLOB_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Lists every entry below `root` as a path relative to it, sorted and
+/// without following symlinks.
+pub fn walk(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    walk_into(root, Path::new(""), &mut paths)?;
+    paths.sort();
+    Ok(paths)
+}
+
+fn walk_into(root: &Path, relative: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(root.join(relative))? {
+        let entry = entry?;
+        let path = relative.join(entry.file_name());
+        let is_dir = entry.file_type()?.is_dir();
+        paths.push(path.clone());
+        if is_dir {
+            walk_into(root, &path, paths)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders the content of `root` as an indented listing, one entry per line,
+/// for use in failure messages.
+pub fn listing(root: &Path) -> String {
+    let paths = match walk(root) {
+        Ok(paths) => paths,
+        Err(e) => return format!("  <failed to list {}: {e}>\n", root.display()),
+    };
+    if paths.is_empty() {
+        return "  <empty>\n".to_string();
+    }
+
+    paths.iter().fold(String::new(), |mut listing, path| {
+        let full = root.join(path);
+        let suffix = match std::fs::symlink_metadata(&full) {
+            Ok(m) if m.file_type().is_symlink() => std::fs::read_link(&full)
+                .map(|target| format!(" -> {}", target.display()))
+                .unwrap_or_default(),
+            Ok(m) if m.is_dir() => "/".to_string(),
+            _ => String::new(),
+        };
+        let _ = writeln!(listing, "  {}{suffix}", display_path(path));
+        listing
+    })
+}
+
+/// Displays a relative path with `/` separators on every platform.
+pub fn display_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -1,16 +1,10 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use thiserror::Error;
+use crate::error::{Operation, Result, ResultExt};
 
-#[derive(Error, Debug)]
-pub enum Error {
-    #[error(transparent)]
-    Yaml(#[from] serde_yaml::Error),
-    #[error(transparent)]
-    IO(#[from] std::io::Error),
-}
-
-pub type Result<T> = std::result::Result<T, Error>;
+/// Environment variable that, when set to anything but `0` or `false`, makes
+/// [`assert_snapshot`] rewrite snapshot files instead of comparing them.
+pub const UPDATE_ENV_VAR: &str = "TREE_FS_UPDATE";
 
 /// Creates a file tree based on the content of a YAML file.
 ///
@@ -19,9 +13,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// Returns a `Result` containing the path to the root folder of the generated file tree on success,
 /// or an error if the operation fails.
 pub fn from_yaml_file(path: &PathBuf) -> Result<crate::Tree> {
-    let f = std::fs::File::open(path)?;
-    let tree_builder: crate::TreeBuilder = serde_yaml::from_reader(f)?;
-    Ok(tree_builder.create()?)
+    crate::TreeBuilder::from_yaml_file(path)?.create()
 }
 
 /// Creates a file tree based on a YAML-formatted string.
@@ -30,11 +22,150 @@ pub fn from_yaml_file(path: &PathBuf) -> Result<crate::Tree> {
 /// Returns a `Result` containing the path to the root folder of the generated file tree on success,
 /// or an error if the operation fails.
 pub fn from_yaml_str(content: &str) -> Result<crate::Tree> {
-    let tree_builder: crate::TreeBuilder = serde_yaml::from_str(content)?;
-    Ok(tree_builder.create()?)
+    crate::TreeBuilder::from_yaml_str(content)?.create()
+}
+
+impl crate::TreeBuilder {
+    /// Parses a YAML string into a builder without creating the tree, so the
+    /// loaded spec can be adjusted before calling [`create`](Self::create).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let tree = tree_fs::TreeBuilder::from_yaml_str(
+    ///     "entries:\n  - path: base.txt\n    type: empty_file\n",
+    /// )
+    /// .expect("parse spec")
+    /// .add_file("extra.txt", "one more file")
+    /// .create()
+    /// .expect("create tree fs");
+    /// assert!(tree.root.join("extra.txt").exists());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content cannot be parsed.
+    pub fn from_yaml_str(content: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(content)?)
+    }
+
+    /// Parses a YAML file into a builder without creating the tree, see
+    /// [`from_yaml_str`](Self::from_yaml_str).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_yaml_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let f = std::fs::File::open(path).context(Operation::Read, path)?;
+        Ok(serde_yaml::from_reader(f)?)
+    }
+
+    /// Serializes the tree to a YAML string that `from_yaml_str` accepts.
+    ///
+    /// Multi-line text content is written as block scalars so generated
+    /// fixtures stay readable. Temporary roots are not serialized, so the
+    /// loaded tree gets a fresh one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree cannot be serialized.
+    pub fn to_yaml_string(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Serializes the tree to a YAML file that `from_yaml_file` accepts, see
+    /// [`to_yaml_string`](Self::to_yaml_string).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree cannot be serialized or the file cannot be
+    /// written.
+    pub fn to_yaml_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let content = self.to_yaml_string()?;
+        std::fs::write(path, content).context(Operation::Write, path)
+    }
+}
+
+/// Asserts that the directory at `dir` matches the YAML snapshot at
+/// `snapshot`.
+///
+/// When the snapshot file does not exist, or the [`UPDATE_ENV_VAR`]
+/// environment variable is set, the directory is captured with
+/// [`crate::TreeBuilder::from_dir_with`] and written to the snapshot instead.
+/// Snapshots use the same format as `from_yaml_file`. Permissions are not
+/// recorded so snapshots do not depend on the platform; use
+/// [`assert_snapshot_with`] to change what is captured.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use tree_fs::TreeBuilder;
+/// let tree = TreeBuilder::default()
+///     .add_file("out/generated.rs", "// generated")
+///     .create()
+///     .expect("create tree fs");
+///
+/// tree_fs::assert_snapshot(&tree.root, "tests/snapshots/generated.yaml");
+/// ```
+///
+/// # Panics
+///
+/// Panics with a report of every difference when the directory does not match
+/// the snapshot, or when the directory or snapshot cannot be read or written.
+#[track_caller]
+pub fn assert_snapshot<P: AsRef<Path>, S: AsRef<Path>>(dir: P, snapshot: S) {
+    assert_snapshot_with(dir, snapshot, &crate::CaptureOptions::new().settings(false));
+}
+
+/// Asserts that the directory at `dir` matches the YAML snapshot at
+/// `snapshot`, capturing the directory with `options`; see
+/// [`assert_snapshot`].
+///
+/// # Panics
+///
+/// Panics with a report of every difference when the directory does not match
+/// the snapshot, or when the directory or snapshot cannot be read or written.
+#[track_caller]
+pub fn assert_snapshot_with<P: AsRef<Path>, S: AsRef<Path>>(
+    dir: P,
+    snapshot: S,
+    options: &crate::CaptureOptions,
+) {
+    let (dir, snapshot) = (dir.as_ref(), snapshot.as_ref());
+
+    if !snapshot.exists() || update_requested() {
+        let captured = crate::TreeBuilder::from_dir_with(dir, options)
+            .unwrap_or_else(|e| panic!("failed to capture `{}`: {e}", dir.display()));
+        if let Some(parent) = snapshot.parent() {
+            std::fs::create_dir_all(parent).unwrap_or_else(|e| {
+                panic!(
+                    "failed to create snapshot folder `{}`: {e}",
+                    parent.display()
+                )
+            });
+        }
+        captured
+            .to_yaml_file(snapshot)
+            .unwrap_or_else(|e| panic!("failed to write snapshot: {e}"));
+        return;
+    }
+
+    let expected = crate::TreeBuilder::from_yaml_file(snapshot)
+        .unwrap_or_else(|e| panic!("failed to read snapshot `{}`: {e}", snapshot.display()));
+    let diff = crate::compare(&expected, dir, crate::CompareMode::Exact)
+        .unwrap_or_else(|e| panic!("failed to compare `{}`: {e}", dir.display()));
+    assert!(
+        diff.is_empty(),
+        "`{}` does not match snapshot `{}`\n{diff}\n\nrun with {UPDATE_ENV_VAR}=1 to update the snapshot",
+        dir.display(),
+        snapshot.display()
+    );
 }
 
-/// Default is to drop the directory when the Tree is dropped
-pub const fn default_drop() -> bool {
-    true
+/// Whether [`UPDATE_ENV_VAR`] asks to rewrite snapshots.
+fn update_requested() -> bool {
+    std::env::var_os(UPDATE_ENV_VAR)
+        .is_some_and(|value| !value.is_empty() && value != "0" && value != "false")
 }
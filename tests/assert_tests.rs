@@ -0,0 +1,62 @@
+use std::panic;
+use tree_fs::{Settings, TreeBuilder};
+
+fn sample_tree() -> tree_fs::Tree {
+    TreeBuilder::default()
+        .add_file("out/report.txt", "status: ok\nwarnings: 0")
+        .add_file_with_settings("out/locked.txt", "locked", Settings::new().readonly(true))
+        .add_directory("out/cache")
+        .add_binary_file("out/data.bin", [1, 2, 3])
+        .create()
+        .expect("Failed to create sample tree")
+}
+
+#[test]
+fn test_passing_assertions() {
+    let tree = sample_tree();
+
+    tree.assert()
+        .matches_glob("out/*.txt")
+        .matches_glob("**/data.bin")
+        .file("out/report.txt")
+        .exists()
+        .is_file()
+        .is_writable()
+        .contains("status: ok")
+        .has_content("status: ok\nwarnings: 0");
+    tree.assert().file("out/locked.txt").is_readonly();
+    tree.assert().path("out/cache").is_dir();
+    tree.assert().path("out/data.bin").has_bytes([1, 2, 3]);
+    tree.assert().path("out/missing.txt").does_not_exist();
+}
+
+#[test]
+#[should_panic(expected = "`out/report.txt` does not contain \"error\"")]
+fn test_contains_failure() {
+    let tree = sample_tree();
+    tree.assert().file("out/report.txt").contains("error");
+}
+
+#[test]
+#[should_panic(expected = "no entry matches `*.txt`")]
+fn test_glob_does_not_cross_directories() {
+    let tree = sample_tree();
+    tree.assert().matches_glob("*.txt");
+}
+
+#[test]
+fn test_failure_message_lists_tree_content() {
+    let tree = sample_tree();
+
+    let message = panic::catch_unwind(|| {
+        tree.assert().path("out/cache").is_file();
+    })
+    .expect_err("Assertion should fail")
+    .downcast::<String>()
+    .expect("Panic message should be a string");
+
+    assert!(message.contains("`out/cache` is not a file"));
+    assert!(message.contains("  out/\n"));
+    assert!(message.contains("  out/cache/\n"));
+    assert!(message.contains("  out/report.txt\n"));
+}
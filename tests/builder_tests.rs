@@ -1,5 +1,5 @@
-use std::fs;
-use tree_fs::TreeBuilder;
+use std::{fs, path::Path};
+use tree_fs::{DropPolicy, Error, Operation, TreeBuilder};
 
 #[test]
 fn test_default_builder() {
@@ -74,6 +74,25 @@ fn test_add_empty_file_methods() {
     );
 }
 
+#[test]
+fn test_add_binary_file() {
+    let bytes = [0x89, b'P', b'N', b'G', 0x00, 0xff, 0xfe];
+    let tree = TreeBuilder::default()
+        .add_binary_file("image.png", bytes)
+        .add_binary_file("nested/blob.bin", vec![0u8; 16])
+        .create()
+        .expect("Failed to create tree with binary files");
+
+    assert_eq!(
+        fs::read(tree.root.join("image.png")).expect("Failed to read image.png"),
+        bytes
+    );
+    assert_eq!(
+        fs::read(tree.root.join("nested/blob.bin")).expect("Failed to read blob.bin"),
+        vec![0u8; 16]
+    );
+}
+
 #[test]
 fn test_add_directory() {
     let tree = TreeBuilder::default()
@@ -201,3 +220,176 @@ fn test_drop_flag() {
     // Clean up
     let _ = fs::remove_dir_all(root_path);
 }
+
+#[test]
+fn test_create_error_names_entry_and_operation() {
+    let err = TreeBuilder::default()
+        .add_file("ok.txt", "fine")
+        .add_hard_link("links/broken", "missing.txt")
+        .create()
+        .expect_err("Hard link to a missing target should fail");
+
+    assert!(matches!(
+        err,
+        Error::Io {
+            operation: Operation::CreateLink,
+            ref path,
+            ..
+        } if path == Path::new("links/broken")
+    ));
+    assert!(err
+        .to_string()
+        .starts_with("failed to create link `links/broken`"));
+}
+
+#[test]
+fn test_temp_root_is_never_reused() {
+    let builder = TreeBuilder::default().add_file("file.txt", "content");
+
+    // Occupy the first candidate to simulate a concurrent run.
+    fs::create_dir_all(&builder.root).expect("Failed to occupy candidate root");
+    fs::write(builder.root.join("file.txt"), "someone else").expect("Failed to write file");
+
+    let tree1 = builder.create().expect("Failed to create first tree");
+    let tree2 = builder.create().expect("Failed to create second tree");
+
+    assert_ne!(tree1.root, builder.root);
+    assert_ne!(tree1.root, tree2.root);
+    assert_eq!(
+        fs::read_to_string(builder.root.join("file.txt")).expect("Failed to read file"),
+        "someone else"
+    );
+    assert_eq!(
+        fs::read_to_string(tree1.root.join("file.txt")).expect("Failed to read file"),
+        "content"
+    );
+
+    fs::remove_dir_all(&builder.root).expect("Failed to clean up occupied root");
+}
+
+#[test]
+fn test_assigned_root_is_explicit() {
+    let parent = TreeBuilder::default()
+        .create()
+        .expect("Failed to create parent tree");
+    let root = parent.root.join("assigned");
+
+    let mut builder = TreeBuilder::default().add_file("file.txt", "content");
+    builder.root.clone_from(&root);
+    let tree = builder.create().expect("Failed to create tree");
+
+    assert_eq!(tree.root, root);
+    assert_eq!(
+        fs::read_to_string(root.join("file.txt")).expect("Failed to read file"),
+        "content"
+    );
+}
+
+#[test]
+fn test_temp_root_naming() {
+    let parent = TreeBuilder::default()
+        .create()
+        .expect("Failed to create parent tree");
+
+    let tree = TreeBuilder::default()
+        .root_folder("ignored")
+        .temp_prefix("fixture-")
+        .temp_suffix("-fixture")
+        .temp_parent(parent.root.join("nested"))
+        .create()
+        .expect("Failed to create tree with temp naming");
+
+    assert_eq!(
+        tree.root.parent(),
+        Some(parent.root.join("nested").as_path())
+    );
+    let name = tree
+        .root
+        .file_name()
+        .and_then(|name| name.to_str())
+        .expect("Root should have a name");
+    assert!(name.starts_with("fixture-"));
+    assert!(name.ends_with("-fixture"));
+}
+
+#[test]
+fn test_failed_create_removes_temp_root() {
+    let parent = TreeBuilder::default()
+        .create()
+        .expect("Failed to create parent tree");
+
+    TreeBuilder::default()
+        .temp_parent(&parent.root)
+        .add_file("written.txt", "partial")
+        .add_hard_link("linked.txt", "missing.txt")
+        .create()
+        .expect_err("A hard link to a missing target should fail");
+
+    let leftovers = fs::read_dir(&parent.root)
+        .expect("Failed to read parent tree")
+        .count();
+    assert_eq!(leftovers, 0);
+}
+
+#[test]
+fn test_failed_create_keeps_explicit_root() {
+    let parent = TreeBuilder::default()
+        .create()
+        .expect("Failed to create parent tree");
+    let root = parent.root.join("explicit");
+
+    TreeBuilder::default()
+        .root_folder(&root)
+        .add_file("written.txt", "partial")
+        .add_hard_link("linked.txt", "missing.txt")
+        .create()
+        .expect_err("A hard link to a missing target should fail");
+
+    assert!(root.join("written.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_temp_root_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tree = TreeBuilder::default()
+        .create()
+        .expect("Failed to create default tree");
+
+    let mode = fs::metadata(&tree.root)
+        .expect("Failed to get metadata")
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o700);
+}
+
+#[test]
+fn test_drop_policy_on_success() {
+    // Kept when the owning thread panics.
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let result = std::thread::spawn(move || {
+        let tree = TreeBuilder::default()
+            .drop_policy(DropPolicy::OnSuccess)
+            .add_file("evidence.txt", "keep me")
+            .create()
+            .expect("Failed to create tree with on_success policy");
+        sender.send(tree.root.clone()).expect("Failed to send root");
+        panic!("simulated test failure");
+    })
+    .join();
+    assert!(result.is_err());
+
+    let kept_root = receiver.recv().expect("Failed to receive root");
+    assert!(kept_root.join("evidence.txt").exists());
+    fs::remove_dir_all(&kept_root).expect("Failed to clean up kept tree");
+
+    // Deleted when everything went fine.
+    let tree = TreeBuilder::default()
+        .drop_policy(DropPolicy::OnSuccess)
+        .create()
+        .expect("Failed to create tree with on_success policy");
+    let root_path = tree.root.clone();
+    drop(tree);
+    assert!(!root_path.exists());
+}
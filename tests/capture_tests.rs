@@ -0,0 +1,118 @@
+use std::path::Path;
+use tree_fs::{compare, CaptureOptions, CompareMode, Kind, Settings, TreeBuilder};
+
+fn source_tree() -> tree_fs::Tree {
+    let builder = TreeBuilder::default()
+        .add_file("src/main.rs", "fn main() {}\n")
+        .add_empty_file("src/empty.rs")
+        .add_binary_file("assets/logo.bin", [0xff, 0x00, 0xfe])
+        .add_directory("empty-dir")
+        .add_file_with_settings("secret.txt", "secret", Settings::new().readonly(true))
+        .add_file("target/debug/app", "large build output");
+    #[cfg(unix)]
+    let builder = builder
+        .resolve_link_targets(false)
+        .add_symlink("src/link.rs", "main.rs");
+    builder.create().expect("Failed to create source tree")
+}
+
+fn kind_of<'a>(builder: &'a TreeBuilder, path: &str) -> Option<&'a Kind> {
+    builder
+        .entries()
+        .iter()
+        .find(|entry| entry.path == Path::new(path))
+        .map(|entry| &entry.kind)
+}
+
+#[test]
+fn test_from_dir_round_trip() {
+    let source = source_tree();
+
+    let captured = TreeBuilder::from_dir(&source.root).expect("Failed to capture directory");
+
+    assert_eq!(kind_of(&captured, "src/empty.rs"), Some(&Kind::EmptyFile));
+    assert_eq!(
+        kind_of(&captured, "assets/logo.bin"),
+        Some(&Kind::BinaryFile {
+            content: vec![0xff, 0x00, 0xfe]
+        })
+    );
+    assert_eq!(
+        kind_of(&captured, "src/main.rs"),
+        Some(&Kind::TextFile {
+            content: "fn main() {}\n".to_string()
+        })
+    );
+    #[cfg(unix)]
+    assert_eq!(
+        kind_of(&captured, "src/link.rs"),
+        Some(&Kind::Symlink {
+            target: "main.rs".into()
+        })
+    );
+
+    let diff = compare(&captured, &source.root, CompareMode::Exact).expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+
+    let copy = captured.create().expect("Failed to recreate captured tree");
+    let diff = compare(&captured, &copy.root, CompareMode::Exact).expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+    copy.assert().file("secret.txt").is_readonly();
+    copy.assert().path("empty-dir").is_dir();
+}
+
+#[test]
+fn test_from_dir_keeps_placeholders() {
+    let source = TreeBuilder::default()
+        .templating(false)
+        .add_file("config.ini", "path={{root}}/x")
+        .create()
+        .expect("Failed to create source tree");
+
+    let captured = TreeBuilder::from_dir(&source.root).expect("Failed to capture directory");
+    let diff = compare(&captured, &source.root, CompareMode::Exact).expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+
+    let copy = captured.create().expect("Failed to recreate captured tree");
+    copy.assert()
+        .file("config.ini")
+        .has_content("path={{root}}/x");
+}
+
+#[test]
+fn test_from_dir_filters() {
+    let source = source_tree();
+
+    let options = CaptureOptions::new()
+        .include("src/**")
+        .include("assets/*")
+        .exclude("**/*.bin")
+        .max_file_size(5)
+        .settings(false);
+    let captured =
+        TreeBuilder::from_dir_with(&source.root, &options).expect("Failed to capture directory");
+
+    let mut paths: Vec<_> = captured
+        .entries()
+        .iter()
+        .map(|entry| entry.path.to_string_lossy().replace('\\', "/"))
+        .collect();
+    paths.sort();
+    #[cfg(unix)]
+    assert_eq!(paths, ["src/empty.rs", "src/link.rs"]);
+    #[cfg(not(unix))]
+    assert_eq!(paths, ["src/empty.rs"]);
+    assert!(captured
+        .entries()
+        .iter()
+        .all(|entry| entry.settings.is_none()));
+}
+
+#[test]
+fn test_from_dir_invalid_glob() {
+    let source = source_tree();
+
+    let err = TreeBuilder::from_dir_with(&source.root, &CaptureOptions::new().include("[oops"))
+        .expect_err("Invalid glob should be rejected");
+    assert!(matches!(err, tree_fs::Error::InvalidGlob { .. }));
+}
@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+use tree_fs::{compare, CompareMode, Difference, Settings, TreeBuilder};
+
+#[test]
+fn test_compare_matching_tree() {
+    let tree = TreeBuilder::default()
+        .add_file("src/main.rs", "fn main() {}\n")
+        .add_empty_file("src/empty.rs")
+        .add_directory("target")
+        .create()
+        .expect("Failed to create tree");
+
+    let expected = TreeBuilder::default()
+        .add_file("src/main.rs", "fn main() {}\n")
+        .add_empty_file("src/empty.rs")
+        .add_directory("target");
+
+    let diff = compare(&expected, &tree.root, CompareMode::Exact).expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+    tree.assert().matches(&expected, CompareMode::Exact);
+}
+
+#[test]
+fn test_compare_reports_differences() {
+    let tree = TreeBuilder::default()
+        .add_file("config.toml", "port = 8080\nhost = \"localhost\"\n")
+        .add_directory("logs")
+        .add_file("extra/nested.txt", "unexpected")
+        .add_file("writable.txt", "content")
+        .create()
+        .expect("Failed to create tree");
+
+    let expected = TreeBuilder::default()
+        .add_file("config.toml", "port = 9090\nhost = \"localhost\"\n")
+        .add_empty_file("logs")
+        .add_file("missing.txt", "where am I")
+        .add_file_with_settings("writable.txt", "content", Settings::new().readonly(true));
+
+    let diff = compare(&expected, &tree.root, CompareMode::Exact).expect("Failed to compare");
+    assert_eq!(diff.differences.len(), 5, "{diff}");
+    assert!(matches!(
+        &diff.differences[0],
+        Difference::ContentMismatch { path, .. } if path == Path::new("config.toml")
+    ));
+    assert_eq!(
+        diff.differences[1],
+        Difference::Extra {
+            path: PathBuf::from("extra")
+        }
+    );
+    assert_eq!(
+        diff.differences[2],
+        Difference::KindMismatch {
+            path: PathBuf::from("logs"),
+            expected: "file",
+            actual: "directory",
+        }
+    );
+    assert_eq!(
+        diff.differences[3],
+        Difference::Missing {
+            path: PathBuf::from("missing.txt")
+        }
+    );
+    assert!(matches!(
+        &diff.differences[4],
+        Difference::SettingsMismatch { path, .. } if path == Path::new("writable.txt")
+    ));
+
+    let report = diff.to_string();
+    assert!(report.contains("~ content mismatch: `config.toml`"));
+    assert!(report.contains("-port = 9090"));
+    assert!(report.contains("+port = 8080"));
+    assert!(report.contains(" host = \"localhost\""));
+}
+
+#[test]
+fn test_compare_at_least_ignores_extra_entries() {
+    let tree = TreeBuilder::default()
+        .add_file("out/generated.rs", "// generated")
+        .add_file("out/other.rs", "// other")
+        .create()
+        .expect("Failed to create tree");
+
+    let expected = TreeBuilder::default().add_file("out/generated.rs", "// generated");
+
+    let diff = compare(&expected, &tree.root, CompareMode::AtLeast).expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+
+    let diff = compare(&expected, &tree.root, CompareMode::Exact).expect("Failed to compare");
+    assert_eq!(
+        diff.differences,
+        [Difference::Extra {
+            path: PathBuf::from("out/other.rs")
+        }]
+    );
+}
+
+#[test]
+#[should_panic(expected = "- missing: `expected.txt`")]
+fn test_assert_matches_failure() {
+    let tree = TreeBuilder::default()
+        .create()
+        .expect("Failed to create tree");
+
+    tree.assert().matches(
+        &TreeBuilder::default().add_empty_file("expected.txt"),
+        CompareMode::AtLeast,
+    );
+}
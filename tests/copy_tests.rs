@@ -0,0 +1,271 @@
+use std::fs;
+
+use tree_fs::{Error, Operation, Tree, TreeBuilder};
+
+fn sample() -> Tree {
+    TreeBuilder::default()
+        .add_file("Cargo.toml", "[package]")
+        .add_file("src/main.rs", "fn main() {}")
+        .add_file("src/lib.rs", "pub mod a;")
+        .add_directory("assets")
+        .create()
+        .expect("Failed to create sample tree")
+}
+
+#[test]
+fn test_copy_directory_with_overrides() {
+    let sample = sample();
+
+    let tree = TreeBuilder::default()
+        .copy_dir_from(&sample.root, "project")
+        .add_file("project/src/main.rs", "fn main() { todo!() }")
+        .add_file("project/README.md", "# Sample")
+        .create()
+        .expect("Failed to create tree");
+
+    let assert = tree.assert();
+    assert.file("project/Cargo.toml").has_content("[package]");
+    assert.file("project/src/lib.rs").has_content("pub mod a;");
+    assert
+        .file("project/src/main.rs")
+        .has_content("fn main() { todo!() }");
+    assert.file("project/README.md").has_content("# Sample");
+    assert.path("project/assets").is_dir();
+    // The source is left untouched.
+    sample
+        .assert()
+        .file("src/main.rs")
+        .has_content("fn main() {}");
+}
+
+#[test]
+fn test_copy_single_file() {
+    let sample = sample();
+
+    let tree = TreeBuilder::default()
+        .copy_from(sample.root.join("Cargo.toml"), "nested/Cargo.toml")
+        .create()
+        .expect("Failed to create tree");
+
+    tree.assert()
+        .file("nested/Cargo.toml")
+        .has_content("[package]");
+}
+
+#[test]
+fn test_copy_into_root() {
+    let sample = sample();
+
+    let tree = TreeBuilder::default()
+        .copy_from(&sample.root, "")
+        .add_file("src/main.rs", "fn main() { todo!() }")
+        .create()
+        .expect("Failed to create tree");
+
+    let diff = tree_fs::compare(
+        &TreeBuilder::default()
+            .add_file("Cargo.toml", "[package]")
+            .add_file("src/main.rs", "fn main() { todo!() }")
+            .add_file("src/lib.rs", "pub mod a;")
+            .add_directory("assets"),
+        &tree.root,
+        tree_fs::CompareMode::Exact,
+    )
+    .expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&tree.root)
+            .expect("Failed to read root metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+}
+
+#[test]
+fn test_copy_missing_source() {
+    let err = TreeBuilder::default()
+        .copy_from("tests/fixtures/does-not-exist", "copy")
+        .create()
+        .expect_err("source does not exist");
+
+    assert!(matches!(
+        &err,
+        Error::Io {
+            operation: Operation::Copy,
+            ..
+        }
+    ));
+    assert!(err.to_string().contains("does-not-exist"), "{err}");
+}
+
+#[test]
+fn test_copy_layers_do_not_conflict() {
+    let sample = sample();
+    let other = TreeBuilder::default()
+        .add_file("src/extra.rs", "// extra")
+        .create()
+        .expect("Failed to create other tree");
+
+    let tree = TreeBuilder::default()
+        .copy_from(&sample.root, "project")
+        .copy_from(&other.root, "project")
+        .create()
+        .expect("Failed to create tree");
+
+    tree.assert().file("project/src/main.rs").exists();
+    tree.assert().file("project/src/extra.rs").exists();
+
+    let diff = tree_fs::compare(
+        &TreeBuilder::default().copy_from(&sample.root, "project"),
+        &tree.root,
+        tree_fs::CompareMode::Exact,
+    )
+    .expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_copy_preserves_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sample = TreeBuilder::default()
+        .add_file_with_settings("run.sh", "#!/bin/sh", tree_fs::Settings::new().mode(0o750))
+        .add_file_with_settings("docs/a.txt", "a", tree_fs::Settings::new().mode(0o640))
+        .add_directory_with_settings("docs", tree_fs::Settings::new().readonly(true))
+        .create()
+        .expect("Failed to create sample tree");
+
+    let tree = TreeBuilder::default()
+        .copy_from(&sample.root, "copy")
+        .create()
+        .expect("Failed to create tree");
+
+    let mode = |path: &str| {
+        fs::metadata(tree.root.join(path))
+            .expect("Failed to read metadata")
+            .permissions()
+            .mode()
+            & 0o777
+    };
+    assert_eq!(mode("copy/run.sh"), 0o750);
+    assert_eq!(mode("copy/docs/a.txt"), 0o640);
+    assert_eq!(mode("copy/docs"), 0o555);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_copy_read_only_entries_can_be_written_over() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sample = TreeBuilder::default()
+        .add_file_with_settings("locked.txt", "old", tree_fs::Settings::new().readonly(true))
+        .add_file("sealed/old.txt", "old")
+        .add_directory_with_settings("sealed", tree_fs::Settings::new().mode(0o555))
+        .create()
+        .expect("Failed to create sample tree");
+
+    let tree = TreeBuilder::default()
+        .copy_from(&sample.root, "copy")
+        .add_file("copy/locked.txt", "new")
+        .add_file("copy/sealed/new.txt", "new")
+        .create()
+        .expect("Failed to write over read-only copies");
+
+    let assert = tree.assert();
+    assert.file("copy/locked.txt").has_content("new");
+    assert.file("copy/sealed/new.txt").has_content("new");
+    let mode = |path: &str| {
+        fs::metadata(tree.root.join(path))
+            .expect("Failed to read metadata")
+            .permissions()
+            .mode()
+            & 0o777
+    };
+    assert_eq!(mode("copy/locked.txt"), 0o444);
+    assert_eq!(mode("copy/sealed"), 0o555);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_copy_symlinks() {
+    let sample = TreeBuilder::default()
+        .add_file("target.txt", "target")
+        .resolve_link_targets(false)
+        .add_symlink("link.txt", "target.txt")
+        .create()
+        .expect("Failed to create sample tree");
+
+    let tree = TreeBuilder::default()
+        .copy_from(&sample.root, "links")
+        .copy_from_following_symlinks(&sample.root, "followed")
+        .create()
+        .expect("Failed to create tree");
+
+    tree.assert().path("links/link.txt").is_symlink();
+    assert_eq!(
+        fs::read_link(tree.root.join("links/link.txt")).expect("Failed to read link"),
+        std::path::Path::new("target.txt")
+    );
+    tree.assert()
+        .file("followed/link.txt")
+        .is_file()
+        .has_content("target");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_copy_cannot_bring_in_escaping_symlink() {
+    let outside = TreeBuilder::default()
+        .add_directory("inside")
+        .create()
+        .expect("Failed to create outside tree");
+    let source = TreeBuilder::default()
+        .add_symlink("link", &outside.root)
+        .create()
+        .expect("Failed to create source tree");
+
+    let err = TreeBuilder::default()
+        .copy_from(&source.root, "project")
+        .add_file("project/link/pwned.txt", "outside")
+        .create()
+        .expect_err("Writing through a copied escaping symlink should be rejected");
+    assert!(matches!(err, Error::UnsafePath { .. }), "{err:?}");
+    assert!(!outside.root.join("pwned.txt").exists());
+
+    // A later copy replaces the symlink instead of writing through it.
+    let layer = TreeBuilder::default()
+        .add_file("link/pwned.txt", "outside")
+        .create()
+        .expect("Failed to create layer tree");
+    let tree = TreeBuilder::default()
+        .copy_from(&source.root, "project")
+        .copy_from(&layer.root, "project")
+        .create()
+        .expect("Failed to create tree");
+    tree.assert().path("project/link").is_dir();
+    assert!(!outside.root.join("pwned.txt").exists());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_copy_from_yaml() {
+    let sample = sample();
+    let yaml = format!(
+        "entries:\n  - path: project\n    type: copy\n    source: {}\n  - path: project/extra.txt\n    type: text_file\n    content: extra\n",
+        sample.root.display()
+    );
+
+    let tree = tree_fs::from_yaml_str(&yaml).expect("Failed to create tree from YAML");
+    tree.assert().file("project/src/main.rs").exists();
+    tree.assert().file("project/extra.txt").has_content("extra");
+
+    let builder = TreeBuilder::from_yaml_str(&yaml).expect("Failed to parse YAML");
+    let round_trip = builder.to_yaml_string().expect("Failed to serialize");
+    assert!(round_trip.contains("type: copy"), "{round_trip}");
+    assert!(!round_trip.contains("follow_symlinks"), "{round_trip}");
+}
@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use tree_fs::Error;
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_from_file_yaml() {
+    let tree = tree_fs::from_file("tests/fixtures/tree.yaml").expect("Failed to load YAML");
+    assert!(tree.root.join("folder/bar.yaml").exists());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_from_file_json() {
+    let tree = tree_fs::from_file("tests/fixtures/tree.json").expect("Failed to load JSON");
+    assert!(tree.root.join("folder/bar.yaml").exists());
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_from_file_toml() {
+    let tree = tree_fs::from_file("tests/fixtures/tree.toml").expect("Failed to load TOML");
+    assert!(tree.root.join("folder/bar.yaml").exists());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_from_file_extension_is_case_insensitive() {
+    let fixtures = tree_fs::TreeBuilder::default()
+        .add_file(
+            "TREE.YML",
+            "entries:\n  - path: a.txt\n    type: empty_file\n",
+        )
+        .create()
+        .expect("Failed to create fixtures tree");
+
+    let tree = tree_fs::from_file(fixtures.root.join("TREE.YML")).expect("Failed to load YML");
+    assert!(tree.root.join("a.txt").is_file());
+}
+
+#[cfg(not(feature = "json"))]
+#[test]
+fn test_from_file_names_missing_feature() {
+    let err = tree_fs::from_file("tests/fixtures/tree.json").expect_err("json is disabled");
+    assert!(matches!(
+        err,
+        Error::FormatDisabled {
+            feature: "json",
+            ..
+        }
+    ));
+    assert!(err.to_string().contains("`json` feature"), "{err}");
+}
+
+#[test]
+fn test_from_file_unknown_extension() {
+    let err = tree_fs::from_file("tests/fixtures/tree.ini").expect_err("unknown extension");
+    assert!(
+        matches!(err, Error::UnknownFormat { path } if path == Path::new("tests/fixtures/tree.ini"))
+    );
+
+    let err = tree_fs::from_file("tests/fixtures/tree").expect_err("missing extension");
+    assert!(matches!(err, Error::UnknownFormat { .. }));
+}
+
+#[cfg(all(feature = "json", feature = "toml"))]
+#[test]
+fn test_builder_from_file_matches_across_formats() {
+    let json =
+        tree_fs::TreeBuilder::from_file("tests/fixtures/tree.json").expect("Failed to parse JSON");
+    let toml =
+        tree_fs::TreeBuilder::from_file("tests/fixtures/tree.toml").expect("Failed to parse TOML");
+    let tree = json.create().expect("Failed to create tree");
+    let diff = tree_fs::compare(&toml, &tree.root, tree_fs::CompareMode::Exact)
+        .expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+}
@@ -0,0 +1,203 @@
+use std::fs;
+use std::path::PathBuf;
+
+use tree_fs::{Error, Generator, Operation, TreeBuilder};
+
+#[test]
+fn test_generated_repeat() {
+    let tree = TreeBuilder::default()
+        .add_generated(
+            "short.txt",
+            Generator::Repeat {
+                pattern: "abc".to_string(),
+                size: 10,
+            },
+        )
+        .add_generated(
+            "long.txt",
+            Generator::Repeat {
+                pattern: "0123456".to_string(),
+                size: 200_003,
+            },
+        )
+        .create()
+        .expect("Failed to create tree");
+
+    tree.assert().file("short.txt").has_content("abcabcabca");
+    let long = fs::read(tree.root.join("long.txt")).expect("Failed to read long.txt");
+    assert_eq!(long.len(), 200_003);
+    assert!(long
+        .iter()
+        .zip(b"0123456".iter().cycle())
+        .all(|(actual, expected)| actual == expected));
+}
+
+#[test]
+fn test_generated_random_is_seeded() {
+    let random = |seed| Generator::Random {
+        size: 100_001,
+        seed,
+    };
+    let tree = TreeBuilder::default()
+        .add_generated("a.bin", random(7))
+        .add_generated("b.bin", random(7))
+        .add_generated("c.bin", random(8))
+        .create()
+        .expect("Failed to create tree");
+
+    let read = |name: &str| fs::read(tree.root.join(name)).expect("Failed to read file");
+    let a = read("a.bin");
+    assert_eq!(a.len(), 100_001);
+    assert_eq!(a, read("b.bin"));
+    assert_ne!(a, read("c.bin"));
+}
+
+#[test]
+fn test_generated_lorem() {
+    let tree = TreeBuilder::default()
+        .add_generated("lorem.txt", Generator::Lorem { lines: 10_000 })
+        .add_generated("empty.txt", Generator::Lorem { lines: 0 })
+        .create()
+        .expect("Failed to create tree");
+
+    let content =
+        fs::read_to_string(tree.root.join("lorem.txt")).expect("Failed to read lorem.txt");
+    assert_eq!(content.lines().count(), 10_000);
+    assert!(content.starts_with("Lorem ipsum dolor sit amet"));
+    assert!(content.ends_with(".\n"));
+    tree.assert().file("empty.txt").has_content("");
+}
+
+#[test]
+fn test_generated_sparse() {
+    let tree = TreeBuilder::default()
+        .add_generated("disk.img", Generator::Sparse { size: 1 << 20 })
+        .create()
+        .expect("Failed to create tree");
+
+    let content = fs::read(tree.root.join("disk.img")).expect("Failed to read disk.img");
+    assert_eq!(content.len(), 1 << 20);
+    assert!(content.iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn test_generated_empty_pattern() {
+    let err = TreeBuilder::default()
+        .add_generated(
+            "bad.txt",
+            Generator::Repeat {
+                pattern: String::new(),
+                size: 1,
+            },
+        )
+        .create()
+        .expect_err("empty patterns cannot fill a file");
+
+    assert!(matches!(
+        err,
+        Error::Io {
+            operation: Operation::Write,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_compare_generated_content() {
+    let expected = TreeBuilder::default()
+        .add_generated("lorem.txt", Generator::Lorem { lines: 11 })
+        .add_generated(
+            "pattern.txt",
+            Generator::Repeat {
+                pattern: "ab".to_string(),
+                size: 100_001,
+            },
+        )
+        .add_generated(
+            "random.bin",
+            Generator::Random {
+                size: 200_000,
+                seed: 3,
+            },
+        )
+        .add_generated("disk.img", Generator::Sparse { size: 4096 });
+    let tree = expected.create().expect("Failed to create tree");
+    let compare = || {
+        tree_fs::compare(&expected, &tree.root, tree_fs::CompareMode::Exact)
+            .expect("Failed to compare")
+    };
+    assert!(compare().is_empty(), "{}", compare());
+
+    let mut random = fs::read(tree.root.join("random.bin")).expect("Failed to read random.bin");
+    random[150_000] ^= 1;
+    fs::write(tree.root.join("random.bin"), random).expect("Failed to write random.bin");
+    let mut disk = fs::read(tree.root.join("disk.img")).expect("Failed to read disk.img");
+    disk[10] = 1;
+    fs::write(tree.root.join("disk.img"), disk).expect("Failed to write disk.img");
+    fs::write(tree.root.join("pattern.txt"), "ab").expect("Failed to write pattern.txt");
+
+    let diff = compare();
+    let paths: Vec<_> = diff
+        .differences
+        .iter()
+        .map(|difference| difference.path().to_path_buf())
+        .collect();
+    assert_eq!(
+        paths,
+        ["disk.img", "pattern.txt", "random.bin"].map(PathBuf::from)
+    );
+    assert!(
+        diff.to_string()
+            .contains("generated content differs: expected 100001 bytes, found 2 bytes"),
+        "{diff}"
+    );
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_generated_from_yaml() {
+    let yaml = r"
+entries:
+  - path: pattern.txt
+    type: generated
+    generator: repeat
+    pattern: ab
+    size: 5
+  - path: random.bin
+    type: generated
+    generator: random
+    size: 16
+  - path: lorem.txt
+    type: generated
+    generator: lorem
+    lines: 2
+  - path: sparse.img
+    type: generated
+    generator: sparse
+    size: 4096
+";
+    let tree = tree_fs::from_yaml_str(yaml).expect("Failed to create tree from YAML");
+
+    let assert = tree.assert();
+    assert.file("pattern.txt").has_content("ababa");
+    assert.file("lorem.txt").contains("Lorem ipsum");
+    let len = |name: &str| {
+        fs::metadata(tree.root.join(name))
+            .expect("Failed to read metadata")
+            .len()
+    };
+    assert_eq!(len("random.bin"), 16);
+    assert_eq!(len("sparse.img"), 4096);
+
+    let builder = TreeBuilder::from_yaml_str(yaml).expect("Failed to parse YAML");
+    assert_eq!(
+        builder.entries()[1].kind,
+        tree_fs::Kind::Generated {
+            generator: Generator::Random { size: 16, seed: 0 }
+        }
+    );
+    let serialized = builder.to_yaml_string().expect("Failed to serialize");
+    assert!(serialized.contains("generator: lorem"), "{serialized}");
+    let round_trip = TreeBuilder::from_yaml_str(&serialized).expect("Failed to parse serialized");
+    assert_eq!(round_trip.entries()[0].kind, builder.entries()[0].kind);
+}
@@ -0,0 +1,30 @@
+#![cfg(all(feature = "macros", feature = "yaml"))]
+
+use std::fs;
+
+#[test]
+fn test_include_yaml_creates_embedded_spec() {
+    let tree = tree_fs::include_yaml!("tests/fixtures/tree.yaml")
+        .create()
+        .expect("Failed to create tree");
+
+    assert_eq!(
+        fs::read_to_string(tree.root.join("folder/bar.yaml")).expect("Failed to read bar.yaml"),
+        "foo: bar\n"
+    );
+    assert!(fs::metadata(tree.root.join("readonly_config.ini"))
+        .expect("Failed to read readonly_config.ini metadata")
+        .permissions()
+        .readonly());
+}
+
+#[test]
+fn test_include_yaml_returns_adjustable_builder() {
+    let tree = tree_fs::include_yaml!("tests/fixtures/tree.yaml")
+        .add_file("extra.txt", "extra")
+        .create()
+        .expect("Failed to create tree");
+
+    tree.assert().file("foo.json").exists();
+    tree.assert().file("extra.txt").has_content("extra");
+}
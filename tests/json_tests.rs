@@ -0,0 +1,70 @@
+#![cfg(feature = "json")]
+
+use std::fs;
+
+#[test]
+fn test_from_json_file() {
+    let tree = tree_fs::from_json_file("tests/fixtures/tree.json")
+        .expect("Failed to create tree from JSON file");
+
+    assert_eq!(
+        fs::read_to_string(tree.root.join("foo.json")).expect("Failed to read foo.json"),
+        "{ \"foo\": \"bar\" }\n"
+    );
+    assert_eq!(
+        fs::read_to_string(tree.root.join("folder/bar.yaml")).expect("Failed to read bar.yaml"),
+        "foo: bar\n"
+    );
+    let metadata = fs::metadata(tree.root.join("readonly_config.ini"))
+        .expect("Failed to read readonly_config.ini metadata");
+    assert!(metadata.permissions().readonly());
+}
+
+#[test]
+fn test_from_json_str_entry_kinds() {
+    let tree = tree_fs::from_json_str(
+        r#"{
+            "drop_policy": "always",
+            "entries": [
+                { "path": "dir", "type": "directory" },
+                { "path": "empty.txt", "type": "empty_file" },
+                { "path": "data.bin", "type": "binary_file", "content": "AAH/" },
+                { "path": "script.sh", "type": "text_file", "content": "", "settings": { "mode": "0640" } }
+            ]
+        }"#,
+    )
+    .expect("Failed to create tree from JSON string");
+
+    assert!(tree.root.join("dir").is_dir());
+    assert!(tree.root.join("empty.txt").is_file());
+    assert_eq!(
+        fs::read(tree.root.join("data.bin")).expect("Failed to read data.bin"),
+        [0x00, 0x01, 0xff]
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata =
+            fs::metadata(tree.root.join("script.sh")).expect("Failed to read script.sh metadata");
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o640);
+    }
+}
+
+#[test]
+fn test_from_json_str_invalid() {
+    let result = tree_fs::from_json_str(r#"{ "entries": [{ "path": "a", "type": "unknown" }] }"#);
+    assert!(matches!(result, Err(tree_fs::Error::Json(_))));
+}
+
+#[test]
+fn test_from_json_str_nested_tree() {
+    let tree = tree_fs::from_json_str(
+        r#"{ "tree": { "src": { "main.rs": "fn main() {}", "empty.rs": null }, "out": {} } }"#,
+    )
+    .expect("Failed to create tree from nested JSON");
+
+    let assert = tree.assert();
+    assert.file("src/main.rs").has_content("fn main() {}");
+    assert.file("src/empty.rs").has_content("");
+    assert.path("out").is_dir();
+}
@@ -0,0 +1,27 @@
+//! Kept in its own test binary since it changes the process environment.
+
+use tree_fs::{DropPolicy, TreeBuilder, KEEP_ENV_VAR};
+
+#[test]
+fn test_keep_env_var_overrides_drop_policy() {
+    std::env::set_var(KEEP_ENV_VAR, "1");
+
+    let tree = TreeBuilder::default()
+        .drop_policy(DropPolicy::Always)
+        .add_file("file.txt", "content")
+        .create()
+        .expect("Failed to create tree");
+    let root_path = tree.root.clone();
+    drop(tree);
+    assert!(root_path.exists());
+    std::fs::remove_dir_all(&root_path).expect("Failed to clean up kept tree");
+
+    std::env::set_var(KEEP_ENV_VAR, "0");
+
+    let tree = TreeBuilder::default()
+        .create()
+        .expect("Failed to create tree");
+    let root_path = tree.root.clone();
+    drop(tree);
+    assert!(!root_path.exists());
+}
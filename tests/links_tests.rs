@@ -0,0 +1,99 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::path::Path;
+use tree_fs::TreeBuilder;
+
+#[test]
+fn test_symlink_resolved_against_root() {
+    let tree = TreeBuilder::default()
+        .add_file("config/real.toml", "key = 1")
+        .add_symlink("config/current.toml", "config/real.toml")
+        .add_symlink("linked-dir", "config")
+        .create()
+        .expect("Failed to create tree with symlinks");
+
+    let link = tree.root.join("config/current.toml");
+    assert!(fs::symlink_metadata(&link)
+        .expect("Failed to get link metadata")
+        .file_type()
+        .is_symlink());
+    assert_eq!(
+        fs::read_link(&link).expect("Failed to read link"),
+        tree.root.join("config/real.toml")
+    );
+    assert_eq!(
+        fs::read_to_string(&link).expect("Failed to read through link"),
+        "key = 1"
+    );
+    assert!(tree.root.join("linked-dir/real.toml").exists());
+}
+
+#[test]
+fn test_symlink_verbatim_target() {
+    let tree = TreeBuilder::default()
+        .resolve_link_targets(false)
+        .add_file("data/file.txt", "data")
+        .add_symlink("data/relative", "file.txt")
+        .add_symlink("dangling", "does/not/exist")
+        .create()
+        .expect("Failed to create tree with verbatim symlinks");
+
+    assert_eq!(
+        fs::read_link(tree.root.join("data/relative")).expect("Failed to read link"),
+        Path::new("file.txt")
+    );
+    assert_eq!(
+        fs::read_to_string(tree.root.join("data/relative")).expect("Failed to read through link"),
+        "data"
+    );
+
+    let dangling = tree.root.join("dangling");
+    assert!(fs::symlink_metadata(&dangling).is_ok());
+    assert!(!dangling.exists());
+}
+
+#[test]
+fn test_hard_link() {
+    use std::os::unix::fs::MetadataExt;
+
+    let tree = TreeBuilder::default()
+        .add_file("original.txt", "shared")
+        .add_hard_link("nested/link.txt", "original.txt")
+        .create()
+        .expect("Failed to create tree with hard link");
+
+    let original = fs::metadata(tree.root.join("original.txt")).expect("Failed to get metadata");
+    let link = fs::metadata(tree.root.join("nested/link.txt")).expect("Failed to get metadata");
+    assert_eq!(original.ino(), link.ino());
+    assert_eq!(original.nlink(), 2);
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_links() {
+    let yaml_content = r"
+        resolve_link_targets: false
+        entries:
+        - path: target.txt
+          type: text_file
+          content: target
+        - path: soft
+          type: symlink
+          target: target.txt
+        - path: hard
+          type: hard_link
+          target: target.txt
+    ";
+
+    let tree = tree_fs::from_yaml_str(yaml_content).expect("Failed to create tree with links");
+
+    assert_eq!(
+        fs::read_link(tree.root.join("soft")).expect("Failed to read link"),
+        Path::new("target.txt")
+    );
+    assert_eq!(
+        fs::read_to_string(tree.root.join("hard")).expect("Failed to read hard link"),
+        "target"
+    );
+}
@@ -0,0 +1,165 @@
+use std::fs;
+
+use tree_fs::{tree, Kind};
+
+#[test]
+fn test_tree_macro_nested_entries() {
+    let name = String::from("generated");
+    let tree = tree! {
+        "src" => {
+            "main.rs" => "fn main() {}",
+            "lib" => {
+                "mod.rs" => format!("// {name}"),
+                "deep" => dir,
+            },
+        },
+        "empty.txt" => empty,
+        "bin" => dir,
+    }
+    .create()
+    .expect("Failed to create tree");
+
+    let assert = tree.assert();
+    assert.file("src/main.rs").has_content("fn main() {}");
+    assert.file("src/lib/mod.rs").has_content("// generated");
+    assert.path("src/lib/deep").is_dir();
+    assert.file("empty.txt").has_content("");
+    assert.path("bin").is_dir();
+}
+
+#[test]
+fn test_tree_macro_entry_order_and_kinds() {
+    let builder = tree! {
+        "a" => { "b.txt" => "b" },
+        "c.txt" => empty
+    };
+
+    let entries: Vec<_> = builder
+        .entries()
+        .iter()
+        .map(|entry| (entry.path.clone(), entry.kind.clone()))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            ("a".into(), Kind::Directory),
+            (
+                "a/b.txt".into(),
+                Kind::TextFile {
+                    content: "b".to_string()
+                }
+            ),
+            ("c.txt".into(), Kind::EmptyFile),
+        ]
+    );
+    assert!(builder
+        .entries()
+        .iter()
+        .all(|entry| entry.settings.is_none()));
+}
+
+#[test]
+fn test_tree_macro_settings() {
+    let tree = tree! {
+        "config.ini" [readonly] => "[general]",
+        "locked" [readonly] => {
+            "inner.txt" => empty,
+        },
+    }
+    .create()
+    .expect("Failed to create tree");
+
+    let assert = tree.assert();
+    assert.file("config.ini").is_readonly();
+    assert.path("locked").is_readonly();
+    assert.file("locked/inner.txt").is_writable();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_tree_macro_settings_with_values() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tree = tree! {
+        "run.sh" [mode = 0o640, executable] => "#!/bin/sh",
+    }
+    .create()
+    .expect("Failed to create tree");
+
+    let mode = fs::metadata(tree.root.join("run.sh"))
+        .expect("Failed to read run.sh metadata")
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o750);
+}
+
+#[test]
+fn test_tree_macro_empty() {
+    let builder = tree! {};
+    assert!(builder.entries().is_empty());
+}
+
+#[test]
+fn test_tree_macro_wide_level() {
+    let name = String::from("last");
+    let builder = tree! {
+        "f000.txt" => "0", "f001.txt" => "1", "f002.txt" => "2", "f003.txt" => "3",
+        "f004.txt" => "4", "f005.txt" => "5", "f006.txt" => "6", "f007.txt" => "7",
+        "f008.txt" => "8", "f009.txt" => "9", "f010.txt" => "10", "f011.txt" => "11",
+        "f012.txt" => "12", "f013.txt" => "13", "f014.txt" => "14", "f015.txt" => "15",
+        "f016.txt" => "16", "f017.txt" => "17", "f018.txt" => "18", "f019.txt" => "19",
+        "f020.txt" => "20", "f021.txt" => "21", "f022.txt" => "22", "f023.txt" => "23",
+        "f024.txt" => "24", "f025.txt" => "25", "f026.txt" => "26", "f027.txt" => "27",
+        "f028.txt" => "28", "f029.txt" => "29", "f030.txt" => "30", "f031.txt" => "31",
+        "f032.txt" => "32", "f033.txt" => "33", "f034.txt" => "34", "f035.txt" => "35",
+        "f036.txt" => "36", "f037.txt" => "37", "f038.txt" => "38", "f039.txt" => "39",
+        "f040.txt" => "40", "f041.txt" => "41", "f042.txt" => "42", "f043.txt" => "43",
+        "f044.txt" => "44", "f045.txt" => "45", "f046.txt" => "46", "f047.txt" => "47",
+        "f048.txt" => "48", "f049.txt" => "49", "f050.txt" => "50", "f051.txt" => "51",
+        "f052.txt" => "52", "f053.txt" => "53", "f054.txt" => "54", "f055.txt" => "55",
+        "f056.txt" => "56", "f057.txt" => "57", "f058.txt" => "58", "f059.txt" => "59",
+        "f060.txt" => "60", "f061.txt" => "61", "f062.txt" => "62", "f063.txt" => "63",
+        "f064.txt" => "64", "f065.txt" => "65", "f066.txt" => "66", "f067.txt" => "67",
+        "f068.txt" => "68", "f069.txt" => "69", "f070.txt" => "70", "f071.txt" => "71",
+        "f072.txt" => "72", "f073.txt" => "73", "f074.txt" => "74", "f075.txt" => "75",
+        "f076.txt" => "76", "f077.txt" => "77", "f078.txt" => "78", "f079.txt" => "79",
+        "f080.txt" => "80", "f081.txt" => "81", "f082.txt" => "82", "f083.txt" => "83",
+        "f084.txt" => "84", "f085.txt" => "85", "f086.txt" => "86", "f087.txt" => "87",
+        "f088.txt" => "88", "f089.txt" => "89", "f090.txt" => "90", "f091.txt" => "91",
+        "f092.txt" => "92", "f093.txt" => "93", "f094.txt" => "94", "f095.txt" => "95",
+        "f096.txt" => "96", "f097.txt" => "97", "f098.txt" => "98", "f099.txt" => "99",
+        "f100.txt" => "100", "f101.txt" => "101", "f102.txt" => "102", "f103.txt" => "103",
+        "f104.txt" => "104", "f105.txt" => "105", "f106.txt" => "106", "f107.txt" => "107",
+        "f108.txt" => "108", "f109.txt" => "109", "f110.txt" => "110", "f111.txt" => "111",
+        "f112.txt" => "112", "f113.txt" => "113", "f114.txt" => "114", "f115.txt" => "115",
+        "f116.txt" => "116", "f117.txt" => "117", "f118.txt" => "118", "f119.txt" => "119",
+        "f120.txt" => "120", "f121.txt" => "121", "f122.txt" => "122", "f123.txt" => "123",
+        "f124.txt" => "124", "f125.txt" => "125", "f126.txt" => "126", "f127.txt" => "127",
+        "f128.txt" => "128", "f129.txt" => "129", "f130.txt" => "130", "f131.txt" => "131",
+        "f132.txt" => "132", "f133.txt" => "133", "f134.txt" => "134", "f135.txt" => "135",
+        "f136.txt" => "136", "f137.txt" => "137", "f138.txt" => "138", "f139.txt" => "139",
+        "f140.txt" => "140", "f141.txt" => "141", "f142.txt" => "142", "f143.txt" => "143",
+        "f144.txt" => "144", "f145.txt" => "145", "f146.txt" => "146", "f147.txt" => "147",
+        "f148.txt" => "148", "f149.txt" => "149", "f150.txt" => "150", "f151.txt" => "151",
+        "f152.txt" => "152", "f153.txt" => "153", "f154.txt" => "154", "f155.txt" => "155",
+        "f156.txt" => "156", "f157.txt" => "157", "f158.txt" => "158", "f159.txt" => "159",
+        "f160.txt" => "160", "f161.txt" => "161", "f162.txt" => "162", "f163.txt" => "163",
+        "f164.txt" => "164", "f165.txt" => "165", "f166.txt" => "166", "f167.txt" => "167",
+        "f168.txt" => "168", "f169.txt" => "169", "f170.txt" => "170", "f171.txt" => "171",
+        "f172.txt" => "172", "f173.txt" => "173", "f174.txt" => "174", "f175.txt" => "175",
+        "f176.txt" => "176", "f177.txt" => "177", "f178.txt" => "178", "f179.txt" => "179",
+        "f180.txt" => "180", "f181.txt" => "181", "f182.txt" => "182", "f183.txt" => "183",
+        "f184.txt" => "184", "f185.txt" => "185", "f186.txt" => "186", "f187.txt" => "187",
+        "f188.txt" => "188", "f189.txt" => "189", "f190.txt" => "190", "f191.txt" => "191",
+        "f192.txt" => "192", "f193.txt" => "193", "f194.txt" => "194", "f195.txt" => "195",
+        "f196.txt" => "196", "f197.txt" => "197", "f198.txt" => "198", "f199.txt" => "199",
+        "last.txt" => (format!("{name}")),
+    };
+
+    assert_eq!(builder.entries().len(), 201);
+    let tree = builder.create().expect("Failed to create tree");
+    let assert = tree.assert();
+    assert.file("f000.txt").has_content("0");
+    assert.file("f199.txt").has_content("199");
+    assert.file("last.txt").has_content("last");
+}
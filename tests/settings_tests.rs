@@ -44,3 +44,150 @@ fn test_readonly_convenience_methods() {
         .permissions();
     assert!(empty_perms.readonly());
 }
+
+#[cfg(unix)]
+#[test]
+fn test_mode_and_executable_settings() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tree = TreeBuilder::default()
+        .add_file_with_settings("secret.key", "key", Settings::new().mode(0o600))
+        .add_file_with_settings(
+            "run.sh",
+            "#!/bin/sh\n",
+            Settings::new().mode(0o644).executable(true),
+        )
+        .add_directory_with_settings("locked", Settings::new().mode(0o500))
+        .add_file("locked/inside.txt", "created before locking")
+        .create()
+        .expect("Failed to create tree with modes");
+
+    let mode = |path: &str| {
+        fs::metadata(tree.root.join(path))
+            .expect("Failed to get metadata")
+            .permissions()
+            .mode()
+            & 0o7777
+    };
+    assert_eq!(mode("secret.key"), 0o600);
+    assert_eq!(mode("run.sh"), 0o755);
+    assert_eq!(mode("locked"), 0o500);
+    assert!(tree.root.join("locked/inside.txt").exists());
+}
+
+#[test]
+fn test_readonly_directory() {
+    let tree = TreeBuilder::default()
+        .add_directory_with_settings("readonly-dir", Settings::new().readonly(true))
+        .add_file("readonly-dir/file.txt", "content")
+        .create()
+        .expect("Failed to create tree with readonly directory");
+
+    let dir = tree.root.join("readonly-dir");
+    let perms = fs::metadata(&dir)
+        .expect("Failed to get metadata")
+        .permissions();
+    assert!(perms.readonly());
+    assert!(dir.join("file.txt").exists());
+}
+
+#[test]
+fn test_timestamp_settings() {
+    use std::time::{Duration, SystemTime};
+    use tree_fs::Timestamp;
+
+    let fixed_time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let tree = TreeBuilder::default()
+        .add_directory_with_settings("cache", Settings::new().modified(fixed_time))
+        .add_file_with_settings(
+            "cache/entry.bin",
+            "cached",
+            Settings::new()
+                .modified(Timestamp::Ago(Duration::from_hours(2)))
+                .accessed(fixed_time),
+        )
+        .create()
+        .expect("Failed to create tree with timestamps");
+
+    // The directory keeps its mtime even though a child was written into it.
+    let dir_meta = fs::metadata(tree.root.join("cache")).expect("Failed to get metadata");
+    assert_eq!(
+        dir_meta.modified().expect("Failed to read mtime"),
+        fixed_time
+    );
+
+    let file_meta =
+        fs::metadata(tree.root.join("cache/entry.bin")).expect("Failed to get metadata");
+    let age = SystemTime::now()
+        .duration_since(file_meta.modified().expect("Failed to read mtime"))
+        .expect("mtime should be in the past");
+    assert!((7_200..7_300).contains(&age.as_secs()));
+    assert_eq!(
+        file_meta.accessed().expect("Failed to read atime"),
+        fixed_time
+    );
+}
+
+#[test]
+fn test_timestamp_out_of_range() {
+    use tree_fs::{Error, Operation, Timestamp};
+
+    let err = TreeBuilder::default()
+        .add_empty_file_with_settings(
+            "future.txt",
+            Settings::new().modified(Timestamp::FromNow(std::time::Duration::MAX)),
+        )
+        .create()
+        .expect_err("The timestamp cannot be represented");
+
+    assert!(matches!(
+        err,
+        Error::Io {
+            operation: Operation::SetTimes,
+            ..
+        }
+    ));
+    assert!(
+        err.to_string().contains("timestamp is out of range"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_readonly_tree_is_removed() {
+    let build = || {
+        TreeBuilder::default()
+            .add_directory_with_settings("locked", Settings::new().readonly(true))
+            .add_readonly_file("locked/file.txt", "read-only content")
+            .add_directory_with_settings("locked/nested", Settings::new().readonly(true))
+            .add_readonly_empty_file("locked/nested/empty.txt")
+            .create()
+            .expect("Failed to create read-only tree")
+    };
+
+    // Removed on drop.
+    let tree = build();
+    let root_path = tree.root.clone();
+    drop(tree);
+    assert!(!root_path.exists());
+
+    // Removed by close, which reports errors.
+    let tree = build();
+    let root_path = tree.root.clone();
+    tree.close().expect("Failed to close read-only tree");
+    assert!(!root_path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_untraversable_tree_is_removed() {
+    let tree = TreeBuilder::default()
+        .add_directory_with_settings("sealed", Settings::new().mode(0o000))
+        .add_file("sealed/inner/file.txt", "content")
+        .create()
+        .expect("Failed to create untraversable tree");
+    let root_path = tree.root.clone();
+
+    tree.close().expect("Failed to close untraversable tree");
+    assert!(!root_path.exists());
+}
@@ -0,0 +1,30 @@
+#![cfg(feature = "yaml")]
+//! Kept in its own test binary since it changes the process environment.
+
+use std::fs;
+use tree_fs::{TreeBuilder, UPDATE_ENV_VAR};
+
+#[test]
+fn test_update_env_var_rewrites_snapshot() {
+    let fixtures = TreeBuilder::default()
+        .add_file(
+            "snapshot.yaml",
+            "entries:\n  - path: a.txt\n    type: text_file\n    content: old\n",
+        )
+        .create()
+        .expect("Failed to create fixtures tree");
+    let snapshot = fixtures.root.join("snapshot.yaml");
+
+    let tree = TreeBuilder::default()
+        .add_file("a.txt", "new")
+        .create()
+        .expect("Failed to create tree");
+
+    std::env::set_var(UPDATE_ENV_VAR, "1");
+    tree_fs::assert_snapshot(&tree.root, &snapshot);
+    std::env::remove_var(UPDATE_ENV_VAR);
+
+    let recorded = fs::read_to_string(&snapshot).expect("Failed to read snapshot");
+    assert!(recorded.contains("new"), "{recorded}");
+    tree_fs::assert_snapshot(&tree.root, &snapshot);
+}
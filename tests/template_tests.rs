@@ -0,0 +1,166 @@
+use std::fs;
+
+use tree_fs::{Error, TreeBuilder};
+
+#[test]
+fn test_vars_in_paths_and_content() {
+    let tree = TreeBuilder::default()
+        .var("name", "demo")
+        .var("port", "8080")
+        .add_file(
+            "{{name}}/config.toml",
+            "name = \"{{ name }}\"\nport = {{port}}\n",
+        )
+        .add_file("paths.txt", "{{root}}/data")
+        .create()
+        .expect("Failed to create tree");
+
+    tree.assert()
+        .file("demo/config.toml")
+        .has_content("name = \"demo\"\nport = 8080\n");
+    assert_eq!(
+        fs::read_to_string(tree.root.join("paths.txt")).expect("Failed to read paths.txt"),
+        format!("{}/data", tree.root.display())
+    );
+}
+
+#[test]
+fn test_unknown_placeholders_are_kept() {
+    let tree = TreeBuilder::default()
+        .add_file(
+            "chart.yaml",
+            "image: {{ image }}\nreplicas: {{ .Values.replicas }}\n",
+        )
+        .add_file("{{dir}}/a.txt", "a")
+        .create()
+        .expect("Failed to create tree");
+
+    tree.assert()
+        .file("chart.yaml")
+        .has_content("image: {{ image }}\nreplicas: {{ .Values.replicas }}\n");
+    tree.assert().file("{{dir}}/a.txt").exists();
+}
+
+#[test]
+fn test_strict_vars_rejects_undefined_variables() {
+    let builder = TreeBuilder::default()
+        .strict_vars(true)
+        .var("name", "demo")
+        .add_file("{{name}}.txt", "{{name}}")
+        .add_file("config.toml", "port = {{port}}");
+
+    let err = builder.create().expect_err("port is not defined");
+    assert!(
+        matches!(&err, Error::UndefinedVariable { path, name } if path.as_os_str() == "config.toml" && name == "port"),
+        "{err}"
+    );
+    assert_eq!(
+        err.to_string(),
+        "undefined variable `port` in entry `config.toml`"
+    );
+    assert!(matches!(
+        builder.validate(),
+        Err(Error::UndefinedVariable { .. })
+    ));
+    assert!(!builder.root.exists());
+
+    // Braces that do not hold a variable name are not placeholders.
+    TreeBuilder::default()
+        .strict_vars(true)
+        .add_file("chart.yaml", "replicas: {{ .Values.replicas }}")
+        .create()
+        .expect("Failed to create tree");
+}
+
+#[test]
+fn test_errors_name_declared_path() {
+    let err = TreeBuilder::default()
+        .var("dir", "../outside")
+        .add_file("{{dir}}/a.txt", "a")
+        .validate()
+        .expect_err("The rendered path escapes the root");
+    let Error::UnsafePath { path, reason } = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(path.as_os_str(), "{{dir}}/a.txt");
+    assert!(
+        reason.contains("rendered as `../outside/a.txt`"),
+        "{reason}"
+    );
+
+    let err = TreeBuilder::default()
+        .var("name", "same")
+        .add_file("{{name}}.txt", "first")
+        .add_file("same.txt", "second")
+        .validate()
+        .expect_err("Both entries render to the same path");
+    assert_eq!(
+        err.to_string(),
+        "conflicting entries:\n  - `{{name}}.txt` is declared more than once with different definitions"
+    );
+}
+
+#[test]
+fn test_templating_can_be_disabled() {
+    let tree = TreeBuilder::default()
+        .templating(false)
+        .var("name", "demo")
+        .add_file("{{name}}.txt", "root is {{root}}")
+        .create()
+        .expect("Failed to create tree");
+
+    tree.assert()
+        .file("{{name}}.txt")
+        .has_content("root is {{root}}");
+}
+
+#[test]
+fn test_compare_substitutes_vars() {
+    let expected = TreeBuilder::default()
+        .var("name", "demo")
+        .add_file("{{name}}.txt", "root is {{root}}");
+    let tree = expected.create().expect("Failed to create tree");
+
+    let diff = tree_fs::compare(&expected, &tree.root, tree_fs::CompareMode::Exact)
+        .expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_vars_from_yaml() {
+    let yaml = r#"
+vars:
+  name: demo
+  port: 8080
+  debug: true
+strict_vars: true
+entries:
+  - path: "{{name}}.toml"
+    type: text_file
+    content: "port = {{port}}\ndebug = {{debug}}\n"
+"#;
+    let tree = tree_fs::from_yaml_str(yaml).expect("Failed to create tree from YAML");
+    tree.assert()
+        .file("demo.toml")
+        .has_content("port = 8080\ndebug = true\n");
+
+    let serialized = TreeBuilder::from_yaml_str(yaml)
+        .expect("Failed to parse YAML")
+        .to_yaml_string()
+        .expect("Failed to serialize");
+    assert!(serialized.contains("port: '8080'"), "{serialized}");
+    assert!(serialized.contains("strict_vars: true"), "{serialized}");
+
+    let err = tree_fs::from_yaml_str(
+        "strict_vars: true\nentries:\n  - path: a.txt\n    type: text_file\n    content: '{{missing}}'\n",
+    )
+    .expect_err("missing is not defined");
+    assert!(matches!(err, Error::UndefinedVariable { .. }), "{err}");
+
+    let tree = tree_fs::from_yaml_str(
+        "templating: false\nentries:\n  - path: a.txt\n    type: text_file\n    content: '{{root}}'\n",
+    )
+    .expect("Failed to create tree from YAML");
+    tree.assert().file("a.txt").has_content("{{root}}");
+}
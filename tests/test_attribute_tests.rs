@@ -0,0 +1,93 @@
+#![cfg(feature = "macros")]
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tree_fs::{Tree, TreeBuilder};
+
+fn fixture() -> TreeBuilder {
+    TreeBuilder::default()
+        .add_file("config.toml", "debug = true")
+        .add_directory("data")
+}
+
+#[tree_fs::test(tree = fixture)]
+fn test_attribute_with_tree_function(tree: &Tree) {
+    let assert = tree.assert();
+    assert.file("config.toml").has_content("debug = true");
+    assert.path("data").is_dir();
+}
+
+#[cfg(feature = "yaml")]
+#[tree_fs::test(yaml = "tests/fixtures/tree.yaml")]
+fn test_attribute_with_yaml(tree: &Tree) {
+    tree.assert()
+        .file("folder/bar.yaml")
+        .has_content("foo: bar\n");
+}
+
+#[tree_fs::test]
+fn test_attribute_without_arguments(tree: &Tree) {
+    assert!(tree.root.is_dir());
+    tree.assert().path("anything").does_not_exist();
+}
+
+#[tree_fs::test(tree = fixture)]
+fn test_attribute_returning_result(tree: &Tree) -> std::io::Result<()> {
+    let content = std::fs::read_to_string(tree.root.join("config.toml"))?;
+    assert_eq!(content, "debug = true");
+    Ok(())
+}
+
+/// Failing tests keep their tree, so keep it out of the system temp folder.
+fn target_fixture() -> TreeBuilder {
+    fixture().temp_parent(env!("CARGO_TARGET_TMPDIR"))
+}
+
+#[tree_fs::test(tree = fixture)]
+#[should_panic(expected = "tree assertion failed")]
+fn test_attribute_keeps_other_attributes(tree: &Tree) {
+    tree.assert().path("missing").exists();
+}
+
+#[test]
+#[allow(unnameable_test_items)]
+fn test_attribute_keeps_tree_on_failure() {
+    static ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+    #[tree_fs::test(tree = target_fixture)]
+    fn failing(tree: &Tree) {
+        *ROOT.lock().expect("Failed to lock root") = Some(tree.root.clone());
+        panic!("test failure");
+    }
+
+    assert!(std::panic::catch_unwind(failing).is_err());
+    let root = ROOT
+        .lock()
+        .expect("Failed to lock root")
+        .take()
+        .expect("Test did not run");
+    assert!(root.join("config.toml").exists());
+    std::fs::remove_dir_all(root).expect("Failed to clean up kept tree");
+}
+
+#[test]
+#[allow(unnameable_test_items)]
+fn test_attribute_removes_tree_of_should_panic_test() {
+    static ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+    #[tree_fs::test(tree = fixture)]
+    #[should_panic(expected = "expected failure")]
+    fn expected_failure(tree: &Tree) {
+        *ROOT.lock().expect("Failed to lock root") = Some(tree.root.clone());
+        panic!("expected failure");
+    }
+
+    assert!(std::panic::catch_unwind(expected_failure).is_err());
+    let root = ROOT
+        .lock()
+        .expect("Failed to lock root")
+        .take()
+        .expect("Test did not run");
+    assert!(!root.exists());
+}
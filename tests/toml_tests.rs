@@ -0,0 +1,74 @@
+#![cfg(feature = "toml")]
+
+use std::fs;
+
+#[test]
+fn test_from_toml_file() {
+    let tree = tree_fs::from_toml_file("tests/fixtures/tree.toml")
+        .expect("Failed to create tree from TOML file");
+
+    assert_eq!(
+        fs::read_to_string(tree.root.join("foo.json")).expect("Failed to read foo.json"),
+        "{ \"foo\": \"bar\" }\n"
+    );
+    assert_eq!(
+        fs::read_to_string(tree.root.join("folder/bar.yaml")).expect("Failed to read bar.yaml"),
+        "foo: bar\n"
+    );
+    let metadata = fs::metadata(tree.root.join("readonly_config.ini"))
+        .expect("Failed to read readonly_config.ini metadata");
+    assert!(metadata.permissions().readonly());
+}
+
+#[test]
+fn test_from_toml_str_entry_kinds() {
+    let tree = tree_fs::from_toml_str(
+        r#"
+drop_policy = "always"
+
+[[entries]]
+path = "dir"
+type = "directory"
+
+[[entries]]
+path = "empty.txt"
+type = "empty_file"
+
+[[entries]]
+path = "data.bin"
+type = "binary_file"
+content = "AAH/"
+
+[[entries]]
+path = "old.txt"
+type = "text_file"
+content = "old"
+settings = { mode = "0640", modified = 1000000000 }
+"#,
+    )
+    .expect("Failed to create tree from TOML string");
+
+    assert!(tree.root.join("dir").is_dir());
+    assert!(tree.root.join("empty.txt").is_file());
+    assert_eq!(
+        fs::read(tree.root.join("data.bin")).expect("Failed to read data.bin"),
+        [0x00, 0x01, 0xff]
+    );
+    let metadata =
+        fs::metadata(tree.root.join("old.txt")).expect("Failed to read old.txt metadata");
+    assert_eq!(
+        metadata.modified().expect("Failed to read mtime"),
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000)
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o640);
+    }
+}
+
+#[test]
+fn test_from_toml_str_invalid() {
+    let result = tree_fs::from_toml_str("[[entries]]\npath = \"a\"\n");
+    assert!(matches!(result, Err(tree_fs::Error::Toml(_))));
+}
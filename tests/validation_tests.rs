@@ -0,0 +1,251 @@
+use std::{fs, path::PathBuf};
+use tree_fs::{Conflict, Error, TreeBuilder};
+
+#[test]
+fn test_rejects_absolute_path() {
+    let absolute = std::env::temp_dir().join("tree-fs-absolute.txt");
+    let builder = TreeBuilder::default()
+        .add_file("ok.txt", "fine")
+        .add_file(&absolute, "should not be written");
+    let root = builder.root.clone();
+
+    let err = builder
+        .create()
+        .expect_err("Absolute path should be rejected");
+
+    assert!(matches!(err, Error::UnsafePath { ref path, .. } if *path == absolute));
+    assert!(!absolute.exists());
+    // Nothing is written before validation fails.
+    assert!(!root.exists());
+}
+
+#[test]
+fn test_rejects_parent_dir_escape() {
+    let err = TreeBuilder::default()
+        .add_file("nested/../../escaped.txt", "outside")
+        .create()
+        .expect_err("Parent directory escape should be rejected");
+
+    assert!(matches!(err, Error::UnsafePath { .. }));
+    assert!(err.to_string().contains("escapes the root folder"));
+}
+
+#[test]
+fn test_allows_parent_dir_inside_root() {
+    let tree = TreeBuilder::default()
+        .add_file("a/../b.txt", "inside")
+        .create()
+        .expect("Parent directory inside the root should be allowed");
+
+    assert_eq!(
+        fs::read_to_string(tree.root.join("b.txt")).expect("Failed to read b.txt"),
+        "inside"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rejects_write_through_escaping_symlink() {
+    let outside = TreeBuilder::default()
+        .create()
+        .expect("Failed to create outside tree");
+
+    let err = TreeBuilder::default()
+        .add_symlink("escape", &outside.root)
+        .add_file("escape/owned.txt", "outside")
+        .create()
+        .expect_err("Writing through an escaping symlink should be rejected");
+
+    assert!(err.to_string().contains("traverses symlink `escape`"));
+    assert!(!outside.root.join("owned.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rejects_write_through_symlink_chain() {
+    let outside = TreeBuilder::default()
+        .create()
+        .expect("Failed to create outside tree");
+
+    let err = TreeBuilder::default()
+        .add_symlink("b", &outside.root)
+        .add_symlink("a", "b")
+        .add_file("a/pwned.txt", "outside")
+        .validate()
+        .expect_err("Writing through a chain of symlinks should be rejected");
+
+    assert!(err.to_string().contains("traverses symlink `b`"));
+    assert!(!outside.root.join("pwned.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rejects_symlink_cycles() {
+    let err = TreeBuilder::default()
+        .add_symlink("a", "b")
+        .add_symlink("b", "a")
+        .add_file("a/file.txt", "content")
+        .validate()
+        .expect_err("Writing through a symlink cycle should be rejected");
+
+    assert!(err.to_string().contains("resolves through more than"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rejects_write_through_existing_symlink() {
+    let outside = TreeBuilder::default()
+        .create()
+        .expect("Failed to create outside tree");
+    let tree = TreeBuilder::default()
+        .add_symlink("escape", &outside.root)
+        .create()
+        .expect("A dangling-free symlink alone is allowed");
+
+    let err = TreeBuilder::default()
+        .root_folder(&tree.root)
+        .add_file("escape/owned.txt", "outside")
+        .create()
+        .expect_err("Writing through an existing escaping symlink should be rejected");
+
+    assert!(err.to_string().contains("traverses existing symlink"));
+    assert!(!outside.root.join("owned.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_rejects_hard_link_target_through_escaping_symlink() {
+    let outside = TreeBuilder::default()
+        .add_file("secret.txt", "outside")
+        .create()
+        .expect("Failed to create outside tree");
+
+    let err = TreeBuilder::default()
+        .add_symlink("out", &outside.root)
+        .add_hard_link("linked.txt", "out/secret.txt")
+        .validate()
+        .expect_err("A hard link target behind an escaping symlink should be rejected");
+
+    assert!(err
+        .to_string()
+        .contains("hard link target traverses symlink `out`"));
+}
+
+#[test]
+fn test_allow_unsafe_paths_opt_out() {
+    let parent = TreeBuilder::default()
+        .create()
+        .expect("Failed to create parent tree");
+
+    let _tree = TreeBuilder::default()
+        .root_folder(parent.root.join("inner"))
+        .allow_unsafe_paths(true)
+        .add_file("../sibling.txt", "escaped on purpose")
+        .create()
+        .expect("Unsafe paths should be allowed when opted out");
+
+    assert_eq!(
+        fs::read_to_string(parent.root.join("sibling.txt")).expect("Failed to read sibling.txt"),
+        "escaped on purpose"
+    );
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_cannot_opt_out_of_path_checks() {
+    let yaml_content = r"
+        allow_unsafe_paths: true
+        entries:
+        - path: ../../escaped.txt
+          type: text_file
+          content: outside
+    ";
+
+    let err = tree_fs::from_yaml_str(yaml_content).expect_err("Escaping YAML should be rejected");
+    assert!(matches!(err, Error::UnsafePath { .. }));
+}
+
+#[test]
+fn test_validate_reports_all_conflicts() {
+    let builder = TreeBuilder::default()
+        .add_file("a/b", "file")
+        .add_file("a/b/c.txt", "inside a file")
+        .add_file("dup.txt", "first")
+        .add_file("dup.txt", "second")
+        .add_directory("mixed")
+        .add_empty_file("mixed");
+    let root = builder.root.clone();
+
+    let err = builder
+        .validate()
+        .expect_err("Conflicts should be detected");
+    let Error::Conflicts(conflicts) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(
+        conflicts,
+        &[
+            Conflict::Duplicate {
+                path: PathBuf::from("dup.txt")
+            },
+            Conflict::KindMismatch {
+                path: PathBuf::from("mixed")
+            },
+            Conflict::Ancestor {
+                path: PathBuf::from("a/b"),
+                descendant: PathBuf::from("a/b/c.txt")
+            },
+        ]
+    );
+    assert!(err.to_string().contains("`a/b` is a file but `a/b/c.txt`"));
+
+    builder
+        .create()
+        .expect_err("create should run the same validation");
+    assert!(!root.exists());
+}
+
+#[test]
+fn test_validate_allows_identical_duplicates() {
+    let builder = TreeBuilder::default()
+        .add_directory("dir")
+        .add_directory("dir/")
+        .add_file("dir/file.txt", "same")
+        .add_file("./dir/file.txt", "same");
+
+    builder
+        .validate()
+        .expect("Identical duplicates are not conflicts");
+}
+
+#[test]
+fn test_validate_rejects_files_at_root() {
+    let builder = TreeBuilder::default()
+        .add_directory(".")
+        .add_empty_file("")
+        .add_file(".", "content");
+    let root = builder.root.clone();
+
+    let err = builder
+        .validate()
+        .expect_err("Files declared at the root should be rejected");
+    let Error::Conflicts(conflicts) = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(
+        conflicts,
+        &[
+            Conflict::Root {
+                path: PathBuf::new()
+            },
+            Conflict::Root {
+                path: PathBuf::from(".")
+            },
+        ]
+    );
+
+    builder
+        .create()
+        .expect_err("create should reject them before allocating the root");
+    assert!(!root.exists());
+}
@@ -24,6 +24,21 @@ fn test_from_yaml_file() {
     );
 }
 
+#[test]
+fn test_from_yaml_file_missing() {
+    let yaml_path = PathBuf::from("tests/fixtures/does-not-exist.yaml");
+    let err = tree_fs::from_yaml_file(&yaml_path).expect_err("Missing YAML file should fail");
+
+    assert!(matches!(
+        err,
+        tree_fs::Error::Io {
+            operation: tree_fs::Operation::Read,
+            ref path,
+            ..
+        } if *path == yaml_path
+    ));
+}
+
 #[test]
 fn test_from_yaml_str() {
     let yaml_content = r"
@@ -85,6 +100,152 @@ fn test_yaml_with_empty_files() {
     );
 }
 
+#[test]
+fn test_yaml_with_binary_files() {
+    let yaml_content = r"
+        entries:
+        - path: image.bin
+          type: binary_file
+          content: iVBORw0KGgo=
+        - path: wrapped.bin
+          type: binary_file
+          content: |
+            AAEC
+            AwQF
+    ";
+
+    let tree = tree_fs::from_yaml_str(yaml_content)
+        .expect("Failed to create tree with binary files from YAML");
+
+    assert_eq!(
+        fs::read(tree.root.join("image.bin")).expect("Failed to read image.bin"),
+        [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']
+    );
+    assert_eq!(
+        fs::read(tree.root.join("wrapped.bin")).expect("Failed to read wrapped.bin"),
+        [0, 1, 2, 3, 4, 5]
+    );
+}
+
+#[test]
+fn test_yaml_invalid_binary_content() {
+    let yaml_content = r"
+        entries:
+        - path: broken.bin
+          type: binary_file
+          content: not*base64
+    ";
+
+    assert!(tree_fs::from_yaml_str(yaml_content).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_yaml_with_mode_settings() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let yaml_content = r#"
+        entries:
+        - path: script.sh
+          type: text_file
+          content: "echo hi"
+          settings:
+            mode: "0640"
+            executable: true
+        - path: private
+          type: directory
+          settings:
+            mode: "0o700"
+    "#;
+
+    let tree =
+        tree_fs::from_yaml_str(yaml_content).expect("Failed to create tree with mode settings");
+
+    let mode = |path: &str| {
+        fs::metadata(tree.root.join(path))
+            .expect("Failed to get metadata")
+            .permissions()
+            .mode()
+            & 0o7777
+    };
+    assert_eq!(mode("script.sh"), 0o750);
+    assert_eq!(mode("private"), 0o700);
+}
+
+#[test]
+fn test_yaml_invalid_mode() {
+    let yaml_content = r#"
+        entries:
+        - path: file.txt
+          type: empty_file
+          settings:
+            mode: "0999"
+    "#;
+
+    assert!(tree_fs::from_yaml_str(yaml_content).is_err());
+}
+
+#[test]
+fn test_yaml_with_timestamps() {
+    use std::time::{Duration, SystemTime};
+
+    let yaml_content = r#"
+        entries:
+        - path: old.txt
+          type: text_file
+          content: old
+          settings:
+            modified: "-2h"
+        - path: dated.txt
+          type: empty_file
+          settings:
+            modified: "2001-09-09T01:46:40Z"
+            accessed: 1000000000
+    "#;
+
+    let tree = tree_fs::from_yaml_str(yaml_content).expect("Failed to create tree with timestamps");
+
+    let old = fs::metadata(tree.root.join("old.txt"))
+        .and_then(|m| m.modified())
+        .expect("Failed to read mtime");
+    let age = SystemTime::now()
+        .duration_since(old)
+        .expect("mtime should be in the past");
+    assert!((7_200..7_300).contains(&age.as_secs()));
+
+    let dated = fs::metadata(tree.root.join("dated.txt")).expect("Failed to get metadata");
+    let expected = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    assert_eq!(dated.modified().expect("Failed to read mtime"), expected);
+    assert_eq!(dated.accessed().expect("Failed to read atime"), expected);
+}
+
+#[test]
+fn test_yaml_timestamps_out_of_range() {
+    let err = tree_fs::TreeBuilder::from_yaml_str(
+        "entries:\n  - path: a.txt\n    type: empty_file\n    settings:\n      modified: 18446744073709551615\n",
+    )
+    .expect_err("The timestamp cannot be represented");
+    assert!(err.to_string().contains("is out of range"), "{err}");
+
+    let builder = tree_fs::TreeBuilder::from_yaml_str(
+        "entries:\n  - path: a.txt\n    type: empty_file\n    settings:\n      modified: \"+500000000000y\"\n",
+    )
+    .expect("Offsets are resolved when the tree is created");
+    let err = builder
+        .create()
+        .expect_err("The offset cannot be represented");
+    assert!(
+        matches!(
+            err,
+            tree_fs::Error::Io {
+                operation: tree_fs::Operation::SetTimes,
+                ..
+            }
+        ),
+        "{err}"
+    );
+}
+
 #[test]
 fn test_yaml_with_directories() {
     let yaml_content = r"
@@ -147,6 +308,24 @@ fn test_yaml_drop_behavior() {
     let _ = fs::remove_dir_all(root_path);
 }
 
+#[test]
+fn test_yaml_drop_policy() {
+    let yaml_content = r"
+        drop: true
+        drop_policy: never
+        entries:
+        - path: file.txt
+          type: empty_file
+    ";
+
+    let tree = tree_fs::from_yaml_str(yaml_content).expect("Failed to create tree with policy");
+    let root_path = tree.root.clone();
+    drop(tree);
+    assert!(root_path.exists());
+
+    fs::remove_dir_all(root_path).expect("Failed to clean up kept tree");
+}
+
 #[test]
 fn test_yaml_custom_root() {
     // Create a custom root in the temp directory
@@ -178,3 +357,202 @@ fn test_yaml_custom_root() {
     // Clean up
     let _ = fs::remove_dir_all(&custom_root);
 }
+
+#[test]
+fn test_to_yaml_string_round_trip() {
+    let builder = tree_fs::TreeBuilder::default()
+        .add_file("config/app.yaml", "name: demo\nport: 8080\n")
+        .add_empty_file("logs/app.log")
+        .add_directory("data")
+        .add_binary_file("data/blob.bin", [0, 159, 146, 150])
+        .add_readonly_file("secret.txt", "s3cret")
+        .drop_policy(tree_fs::DropPolicy::OnSuccess);
+
+    let yaml = builder.to_yaml_string().expect("Failed to serialize tree");
+
+    assert!(yaml.contains("  content: |\n    name: demo\n    port: 8080\n"));
+    assert!(yaml.contains("  content: AJ+Slg==\n"));
+    assert!(yaml.contains("drop_policy: on_success\n"));
+    assert!(!yaml.contains("root:"));
+
+    let tree = tree_fs::from_yaml_str(&yaml).expect("Failed to load serialized tree");
+    let diff = tree_fs::compare(&builder, &tree.root, tree_fs::CompareMode::Exact)
+        .expect("Failed to compare");
+    assert!(diff.is_empty(), "{diff}");
+}
+
+#[test]
+fn test_to_yaml_file_round_trip() {
+    let fixtures = tree_fs::TreeBuilder::default()
+        .create()
+        .expect("Failed to create fixtures tree");
+    let yaml_path = fixtures.root.join("tree.yaml");
+
+    let builder = tree_fs::TreeBuilder::default()
+        .root_folder(fixtures.root.join("custom-root"))
+        .drop(false)
+        .add_file("file.txt", "content");
+    builder
+        .to_yaml_file(&yaml_path)
+        .expect("Failed to write YAML file");
+
+    let tree = tree_fs::from_yaml_file(&yaml_path).expect("Failed to load YAML file");
+    assert_eq!(tree.root, fixtures.root.join("custom-root"));
+    assert_eq!(
+        fs::read_to_string(tree.root.join("file.txt")).expect("Failed to read file.txt"),
+        "content"
+    );
+}
+
+#[test]
+fn test_assert_snapshot_records_then_compares() {
+    let fixtures = tree_fs::TreeBuilder::default()
+        .create()
+        .expect("Failed to create fixtures tree");
+    let snapshot = fixtures.root.join("snapshots/output.yaml");
+
+    let tree = tree_fs::TreeBuilder::default()
+        .add_file("src/main.rs", "fn main() {}\n")
+        .add_empty_file("empty.txt")
+        .create()
+        .expect("Failed to create tree");
+
+    tree_fs::assert_snapshot(&tree.root, &snapshot);
+    assert!(snapshot.exists());
+    let recorded = fs::read_to_string(&snapshot).expect("Failed to read snapshot");
+    assert!(recorded.contains("src/main.rs"));
+
+    // The second run compares against the recorded snapshot.
+    tree_fs::assert_snapshot(&tree.root, &snapshot);
+}
+
+#[test]
+fn test_assert_snapshot_keeps_placeholders() {
+    let fixtures = tree_fs::TreeBuilder::default()
+        .create()
+        .expect("Failed to create fixtures tree");
+    let snapshot = fixtures.root.join("snapshot.yaml");
+
+    let tree = tree_fs::TreeBuilder::default()
+        .templating(false)
+        .add_file("config.ini", "path={{root}}/x")
+        .create()
+        .expect("Failed to create tree");
+
+    tree_fs::assert_snapshot(&tree.root, &snapshot);
+    let recorded = fs::read_to_string(&snapshot).expect("Failed to read snapshot");
+    assert!(recorded.contains("templating: false"), "{recorded}");
+
+    tree_fs::assert_snapshot(&tree.root, &snapshot);
+    let recreated = tree_fs::TreeBuilder::from_yaml_file(&snapshot)
+        .expect("Failed to read snapshot")
+        .create()
+        .expect("Failed to recreate snapshot");
+    recreated
+        .assert()
+        .file("config.ini")
+        .has_content("path={{root}}/x");
+}
+
+#[test]
+fn test_assert_snapshot_reports_differences() {
+    let fixtures = tree_fs::TreeBuilder::default()
+        .add_file(
+            "snapshot.yaml",
+            "entries:\n  - path: a.txt\n    type: text_file\n    content: old\n",
+        )
+        .create()
+        .expect("Failed to create fixtures tree");
+
+    let tree = tree_fs::TreeBuilder::default()
+        .add_file("a.txt", "new")
+        .add_empty_file("b.txt")
+        .create()
+        .expect("Failed to create tree");
+
+    let result = std::panic::catch_unwind(|| {
+        tree_fs::assert_snapshot(&tree.root, fixtures.root.join("snapshot.yaml"));
+    });
+    let message = *result
+        .expect_err("snapshot should not match")
+        .downcast::<String>()
+        .expect("panic message");
+    assert!(message.contains("a.txt"), "{message}");
+    assert!(message.contains("+ extra"), "{message}");
+    assert!(message.contains("TREE_FS_UPDATE=1"), "{message}");
+}
+
+#[test]
+fn test_builder_from_yaml_can_be_adjusted() {
+    let fixtures = tree_fs::TreeBuilder::default()
+        .create()
+        .expect("Failed to create fixtures tree");
+
+    let builder = tree_fs::TreeBuilder::from_yaml_file("tests/fixtures/tree.yaml")
+        .expect("Failed to parse YAML file");
+    assert_eq!(builder.entries().len(), 3);
+    // Nothing is created until `create` is called.
+    assert!(!builder.root.exists());
+
+    let tree = builder
+        .root_folder(fixtures.root.join("custom"))
+        .add_file("extra.txt", "extra")
+        .create()
+        .expect("Failed to create tree");
+    assert_eq!(tree.root, fixtures.root.join("custom"));
+    assert!(tree.root.join("foo.json").is_file());
+    assert_eq!(
+        fs::read_to_string(tree.root.join("extra.txt")).expect("Failed to read extra.txt"),
+        "extra"
+    );
+
+    let builder = tree_fs::TreeBuilder::from_yaml_str("entries: []\n")
+        .expect("Failed to parse YAML string")
+        .drop(false);
+    let tree = builder.create().expect("Failed to create tree");
+    let root = tree.root.clone();
+    drop(tree);
+    assert!(root.exists());
+    fs::remove_dir_all(root).expect("Failed to clean up kept tree");
+}
+
+#[test]
+fn test_from_yaml_str_nested_tree() {
+    let tree = tree_fs::from_yaml_str(
+        r#"
+tree:
+  src:
+    main.rs: "fn main() {}"
+    lib:
+      mod.rs: |
+        pub mod a;
+  assets: {}
+  .keep: null
+entries:
+  - path: config.ini
+    type: text_file
+    content: "[general]"
+    settings:
+      readonly: true
+"#,
+    )
+    .expect("Failed to create tree from nested YAML");
+
+    let assert = tree.assert();
+    assert.file("src/main.rs").has_content("fn main() {}");
+    assert.file("src/lib/mod.rs").has_content("pub mod a;\n");
+    assert.path("assets").is_dir();
+    assert.file(".keep").has_content("");
+    assert.file("config.ini").is_readonly();
+}
+
+#[test]
+fn test_from_yaml_str_nested_tree_rejects_other_values() {
+    let result = tree_fs::from_yaml_str("tree:\n  count.txt: [1, 2]\n");
+    let err = result.expect_err("sequences are not entries");
+    assert!(
+        err.to_string()
+            .contains("a string (text file), null (empty file) or a mapping"),
+        "{err}"
+    );
+}
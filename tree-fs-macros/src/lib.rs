@@ -0,0 +1,174 @@
+//! Procedural macros for `tree-fs`, re-exported by the `tree-fs` crate when
+//! its `macros` feature is enabled.
+
+use std::path::{Path, PathBuf};
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, ItemFn, LitStr};
+use tree_fs_spec::{Entry, TreeSpec};
+
+/// Where the tree of a test comes from.
+enum Source {
+    Empty,
+    Yaml(LitStr),
+    Function(syn::Path),
+}
+
+/// Runs a test with a tree created for it.
+///
+/// The test function takes the tree as its only parameter:
+///
+/// ```rust,ignore
+/// #[tree_fs::test(yaml = "tests/fixtures/tree.yaml")]
+/// fn loads_config(tree: &tree_fs::Tree) {
+///     assert!(tree.root.join("foo.json").exists());
+/// }
+///
+/// fn fixture() -> tree_fs::TreeBuilder {
+///     tree_fs::TreeBuilder::default().add_file("a.txt", "a")
+/// }
+///
+/// #[tree_fs::test(tree = fixture)]
+/// fn reads_file(tree: &tree_fs::Tree) {
+///     tree.assert().file("a.txt").has_content("a");
+/// }
+/// ```
+///
+/// `yaml` paths are relative to the crate's `CARGO_MANIFEST_DIR`, and `tree`
+/// names a function returning a `TreeBuilder`. Without arguments the test
+/// gets an empty temporary folder. The tree is kept when the test fails;
+/// `#[should_panic]` tests pass by panicking, so theirs is always removed.
+#[proc_macro_attribute]
+pub fn test(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut source = Source::Empty;
+    let parser = syn::meta::parser(|meta| {
+        if !matches!(source, Source::Empty) {
+            return Err(meta.error("expected a single `yaml` or `tree` argument"));
+        }
+        if meta.path.is_ident("yaml") {
+            source = Source::Yaml(meta.value()?.parse()?);
+        } else if meta.path.is_ident("tree") {
+            source = Source::Function(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("unsupported argument, expected `yaml` or `tree`"));
+        }
+        Ok(())
+    });
+    parse_macro_input!(args with parser);
+
+    let function = parse_macro_input!(item as ItemFn);
+    expand(&source, &function)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(source: &Source, function: &ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let signature = &function.sig;
+    if let Some(asyncness) = signature.asyncness {
+        return Err(syn::Error::new(
+            asyncness.span(),
+            "async tests are not supported",
+        ));
+    }
+    if signature.inputs.len() != 1 {
+        return Err(syn::Error::new(
+            signature.inputs.span(),
+            "expected the test to take the tree as its only parameter, e.g. `tree: &tree_fs::Tree`",
+        ));
+    }
+
+    let builder = match source {
+        Source::Empty => quote!(::tree_fs::TreeBuilder::default()),
+        Source::Yaml(path) => quote! {
+            ::tree_fs::__private::from_yaml_file(::std::env!("CARGO_MANIFEST_DIR"), #path)
+        },
+        Source::Function(path) => quote!(#path()),
+    };
+
+    let attrs = &function.attrs;
+    let keep_on_failure = !attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("should_panic"));
+    let vis = &function.vis;
+    let name = &signature.ident;
+    let output = &signature.output;
+    // The attributes, such as `#[should_panic]`, belong to the generated test.
+    let inner = ItemFn {
+        attrs: Vec::new(),
+        ..function.clone()
+    };
+
+    Ok(quote! {
+        #[test]
+        #(#attrs)*
+        #vis fn #name() #output {
+            #inner
+            let tree = ::tree_fs::__private::create(#builder, #keep_on_failure);
+            #name(&tree)
+        }
+    })
+}
+
+/// Embeds a YAML spec in the binary and expands to the `TreeBuilder` it
+/// describes.
+///
+/// The path is relative to the crate's `CARGO_MANIFEST_DIR`, and the spec is
+/// parsed with the same model `tree-fs` loads it with when the crate is
+/// compiled, so a malformed spec is a compile error. So are entries escaping
+/// the root folder and conflicting entries, which `TreeBuilder::validate`
+/// would reject.
+///
+/// ```rust,ignore
+/// let tree = tree_fs::include_yaml!("tests/fixtures/tree.yaml")
+///     .create()
+///     .expect("create tree fs");
+/// ```
+#[proc_macro]
+pub fn include_yaml(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as LitStr);
+    include(&path)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn include(path: &LitStr) -> syn::Result<proc_macro2::TokenStream> {
+    let manifest_dir =
+        std::env::var_os("CARGO_MANIFEST_DIR").map_or_else(|| PathBuf::from("."), PathBuf::from);
+    let full_path = manifest_dir.join(path.value());
+
+    let content = std::fs::read_to_string(&full_path).map_err(|e| {
+        syn::Error::new(
+            path.span(),
+            format!("failed to read `{}`: {e}", full_path.display()),
+        )
+    })?;
+    let invalid = |e: &dyn std::fmt::Display| {
+        syn::Error::new(
+            path.span(),
+            format!("invalid tree spec `{}`: {e}", full_path.display()),
+        )
+    };
+    let spec = serde_yaml::from_str::<TreeSpec>(&content).map_err(|e| invalid(&e))?;
+    let mut entries = Vec::new();
+    spec.tree.expand(Path::new(""), &mut entries);
+    entries.extend(spec.entries);
+    check(&entries).map_err(|e| invalid(&e))?;
+
+    let full_path = full_path.to_string_lossy();
+    Ok(quote! {
+        ::tree_fs::__private::from_yaml_str(::std::include_str!(#full_path))
+    })
+}
+
+/// Runs the checks of `TreeBuilder::validate` that need no file system.
+fn check(entries: &[Entry]) -> Result<(), String> {
+    for entry in entries {
+        tree_fs_spec::check_path(&entry.path)
+            .map_err(|reason| format!("unsafe entry path `{}`: {reason}", entry.path.display()))?;
+    }
+    tree_fs_spec::check_conflicts(entries).map_err(|conflicts| {
+        let conflicts: Vec<_> = conflicts.iter().map(ToString::to_string).collect();
+        format!("conflicting entries: {}", conflicts.join(", "))
+    })
+}
@@ -0,0 +1,182 @@
+//! Checks that need no file system, shared by `tree-fs` and its macros so
+//! embedded specs are rejected at compile time for the same reasons.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{Entry, Kind};
+
+/// A conflict between two entries of the same tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// The same path is declared more than once with different definitions.
+    Duplicate {
+        /// Path of the entry as declared.
+        path: PathBuf,
+    },
+    /// The same path is declared both as a directory and as a file or link.
+    KindMismatch {
+        /// Path of the entry as declared.
+        path: PathBuf,
+    },
+    /// A file or hard link is declared as the ancestor of another entry.
+    Ancestor {
+        /// Path of the file entry as declared.
+        path: PathBuf,
+        /// Path of the entry that would be created inside it.
+        descendant: PathBuf,
+    },
+    /// A file or link is declared at the root folder itself.
+    Root {
+        /// Path of the entry as declared.
+        path: PathBuf,
+    },
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate { path } => write!(
+                f,
+                "`{}` is declared more than once with different definitions",
+                path.display()
+            ),
+            Self::KindMismatch { path } => write!(
+                f,
+                "`{}` is declared both as a directory and as a file",
+                path.display()
+            ),
+            Self::Ancestor { path, descendant } => write!(
+                f,
+                "`{}` is a file but `{}` is declared inside it",
+                path.display(),
+                descendant.display()
+            ),
+            Self::Root { path } => write!(
+                f,
+                "`{}` is the root folder, only a directory or a copy can be declared there",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Lexically normalizes a relative path, resolving `.` and `..` components.
+///
+/// Returns `None` when the path is absolute or climbs above its starting
+/// point.
+#[must_use]
+pub fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => normalized.push(part),
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+/// Checks that `path` is relative and does not climb above the root folder,
+/// and returns it normalized.
+///
+/// # Errors
+///
+/// Returns the reason the path is rejected.
+pub fn check_path(path: &Path) -> Result<PathBuf, &'static str> {
+    if path.has_root() || path.is_absolute() {
+        return Err("absolute paths are not allowed");
+    }
+    normalize(path).ok_or("escapes the root folder")
+}
+
+/// Checks entries against each other and reports every conflict found.
+///
+/// Detects paths declared twice with different definitions, paths declared
+/// both as a directory and as a file, and files declared as the ancestor of
+/// another entry, as well as files and links declared at the root folder
+/// itself. Copies may share their path with other entries. Paths
+/// escaping the root are left to [`check_path`].
+///
+/// # Errors
+///
+/// Returns every conflict found, in declaration order.
+pub fn check_conflicts(entries: &[Entry]) -> Result<(), Vec<Conflict>> {
+    let mut conflicts = Vec::new();
+    let mut declared: HashMap<PathBuf, &Entry> = HashMap::new();
+    let mut files = HashSet::new();
+
+    for entry in entries {
+        let Some(normalized) = normalize(&entry.path) else {
+            continue;
+        };
+
+        if normalized.as_os_str().is_empty()
+            && !matches!(entry.kind, Kind::Directory | Kind::Copy { .. })
+        {
+            conflicts.push(Conflict::Root {
+                path: entry.path.clone(),
+            });
+            continue;
+        }
+
+        if let Some(previous) = declared.get(&normalized) {
+            let is_dir = matches!(entry.kind, Kind::Directory);
+            let is_copy = |entry: &Entry| matches!(entry.kind, Kind::Copy { .. });
+            // Copies are layered: later entries write over what they copied.
+            let conflict = if is_copy(entry) || is_copy(previous) {
+                None
+            } else if is_dir != matches!(previous.kind, Kind::Directory) {
+                Some(Conflict::KindMismatch {
+                    path: entry.path.clone(),
+                })
+            } else if previous.kind != entry.kind || previous.settings != entry.settings {
+                Some(Conflict::Duplicate {
+                    path: entry.path.clone(),
+                })
+            } else {
+                None
+            };
+            conflicts.extend(conflict.filter(|conflict| !conflicts.contains(conflict)));
+        } else {
+            declared.insert(normalized.clone(), entry);
+        }
+
+        // Symlinks and copies may be directories, so only real files are checked.
+        if !matches!(
+            entry.kind,
+            Kind::Directory | Kind::Symlink { .. } | Kind::Copy { .. }
+        ) {
+            files.insert(normalized);
+        }
+    }
+
+    for entry in entries {
+        let Some(normalized) = normalize(&entry.path) else {
+            continue;
+        };
+        for ancestor in normalized.ancestors().skip(1) {
+            if files.contains(ancestor) {
+                conflicts.push(Conflict::Ancestor {
+                    path: declared[ancestor].path.clone(),
+                    descendant: entry.path.clone(),
+                });
+            }
+        }
+    }
+
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(conflicts)
+    }
+}
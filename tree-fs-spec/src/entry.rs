@@ -0,0 +1,372 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// When the root folder of a tree is deleted on drop.
+///
+/// Whatever the policy, setting the `TREE_FS_KEEP` environment variable keeps
+/// the tree and prints its path to stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DropPolicy {
+    /// Always delete the root folder.
+    #[default]
+    Always,
+    /// Never delete the root folder.
+    Never,
+    /// Delete the root folder unless the thread is panicking, e.g. because a
+    /// test failed, in which case its path is printed to stderr.
+    OnSuccess,
+}
+
+impl From<bool> for DropPolicy {
+    fn from(drop: bool) -> Self {
+        if drop {
+            Self::Always
+        } else {
+            Self::Never
+        }
+    }
+}
+
+/// Settings for entries in the tree.
+/// Supports the read-only flag, Unix permission modes and timestamps, and can
+/// be extended with additional settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(Default)]
+pub struct Settings {
+    /// Whether the file is read-only.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub readonly: bool,
+    /// Explicit permission mode, e.g. `0o600`.
+    ///
+    /// In YAML the mode is written as an octal string such as `"0755"`. On
+    /// platforms without Unix permissions only the owner write bit is honored,
+    /// by toggling the read-only flag.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none", with = "octal_mode")
+    )]
+    pub mode: Option<u32>,
+    /// Whether the entry is executable.
+    ///
+    /// Adds the execute bit for every class that can read the entry. Ignored
+    /// on platforms without Unix permissions.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub executable: bool,
+    /// Modification time of the entry.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub modified: Option<Timestamp>,
+    /// Access time of the entry.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub accessed: Option<Timestamp>,
+    // Future settings could be added here:
+    // pub owner: Option<String>,
+    // etc.
+}
+
+// Builder pattern for Settings
+impl Settings {
+    /// Creates a new Settings with default values.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the file is read-only.
+    #[must_use]
+    pub const fn readonly(mut self, value: bool) -> Self {
+        self.readonly = value;
+        self
+    }
+
+    /// Sets an explicit permission mode, e.g. `0o600`.
+    #[must_use]
+    pub const fn mode(mut self, mode: u32) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Sets whether the entry is executable.
+    #[must_use]
+    pub const fn executable(mut self, value: bool) -> Self {
+        self.executable = value;
+        self
+    }
+
+    /// Sets the modification time of the entry.
+    #[must_use]
+    pub fn modified<T: Into<Timestamp>>(mut self, time: T) -> Self {
+        self.modified = Some(time.into());
+        self
+    }
+
+    /// Sets the access time of the entry.
+    #[must_use]
+    pub fn accessed<T: Into<Timestamp>>(mut self, time: T) -> Self {
+        self.accessed = Some(time.into());
+        self
+    }
+}
+
+/// A point in time used for entry timestamps.
+///
+/// In YAML a timestamp is either an RFC 3339 date (`2024-01-01T00:00:00Z`),
+/// a number of seconds since the Unix epoch, or an offset relative to when the
+/// tree is created, such as `-2h` or `+1d 12h`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timestamp {
+    /// An absolute point in time.
+    At(SystemTime),
+    /// A duration before the tree is created.
+    Ago(Duration),
+    /// A duration after the tree is created.
+    FromNow(Duration),
+}
+
+impl Timestamp {
+    /// Resolves the timestamp to an absolute point in time.
+    ///
+    /// Offsets reaching before the Unix epoch resolve to the epoch; returns
+    /// `None` when the time is too far in the future to be represented.
+    #[must_use]
+    pub fn resolve(self, now: SystemTime) -> Option<SystemTime> {
+        match self {
+            Self::At(time) => Some(time),
+            Self::Ago(offset) => Some(now.checked_sub(offset).unwrap_or(SystemTime::UNIX_EPOCH)),
+            Self::FromNow(offset) => now.checked_add(offset),
+        }
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(time: SystemTime) -> Self {
+        Self::At(time)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::str::FromStr for Timestamp {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let parse_offset = |offset: &str| {
+            humantime::parse_duration(offset.trim())
+                .map_err(|e| format!("invalid timestamp offset `{value}`: {e}"))
+        };
+
+        if let Some(offset) = value.strip_prefix('-') {
+            Ok(Self::Ago(parse_offset(offset)?))
+        } else if let Some(offset) = value.strip_prefix('+') {
+            Ok(Self::FromNow(parse_offset(offset)?))
+        } else {
+            humantime::parse_rfc3339_weak(value)
+                .map(Self::At)
+                .map_err(|e| format!("invalid timestamp `{value}`: {e}"))
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Timestamp {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::At(time) => serializer.collect_str(&humantime::format_rfc3339(*time)),
+            Self::Ago(offset) => {
+                serializer.collect_str(&format_args!("-{}", humantime::format_duration(*offset)))
+            }
+            Self::FromNow(offset) => {
+                serializer.collect_str(&format_args!("+{}", humantime::format_duration(*offset)))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Timestamp;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(
+                    "an RFC 3339 date, seconds since the Unix epoch or an offset like `-2h`",
+                )
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, secs: u64) -> Result<Timestamp, E> {
+                SystemTime::UNIX_EPOCH
+                    .checked_add(Duration::from_secs(secs))
+                    .map(Timestamp::At)
+                    .ok_or_else(|| E::custom(format!("timestamp `{secs}` is out of range")))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, secs: i64) -> Result<Timestamp, E> {
+                let offset = Duration::from_secs(secs.unsigned_abs());
+                if secs < 0 {
+                    SystemTime::UNIX_EPOCH.checked_sub(offset)
+                } else {
+                    SystemTime::UNIX_EPOCH.checked_add(offset)
+                }
+                .map(Timestamp::At)
+                .ok_or_else(|| E::custom(format!("timestamp `{secs}` is out of range")))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Timestamp, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod octal_mode {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    // serde hands `with` serializers a reference to the field.
+    #[allow(clippy::ref_option, clippy::trivially_copy_pass_by_ref)]
+    pub fn serialize<S: Serializer>(mode: &Option<u32>, serializer: S) -> Result<S::Ok, S::Error> {
+        match mode {
+            Some(mode) => serializer.serialize_str(&format!("{mode:04o}")),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<u32>, D::Error> {
+        let Some(value) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let digits = value.strip_prefix("0o").unwrap_or(&value);
+        u32::from_str_radix(digits, 8)
+            .ok()
+            .filter(|mode| *mode <= 0o7777)
+            .map(Some)
+            .ok_or_else(|| {
+                D::Error::custom(format!(
+                    "invalid mode `{value}`, expected an octal string such as \"0644\""
+                ))
+            })
+    }
+}
+
+/// Describes what kind of entry to create
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum Kind {
+    /// A directory
+    #[cfg_attr(feature = "serde", serde(rename = "directory"))]
+    Directory,
+    /// An empty file
+    #[cfg_attr(feature = "serde", serde(rename = "empty_file"))]
+    EmptyFile,
+    /// A file with text content
+    #[cfg_attr(feature = "serde", serde(rename = "text_file"))]
+    TextFile { content: String },
+    /// A file with binary content
+    ///
+    /// In YAML the content is expected to be base64 encoded.
+    #[cfg_attr(feature = "serde", serde(rename = "binary_file"))]
+    BinaryFile {
+        #[cfg_attr(feature = "serde", serde(with = "base64_content"))]
+        content: Vec<u8>,
+    },
+    /// A symbolic link pointing to `target`
+    ///
+    /// Relative targets are resolved against the tree root unless
+    /// `TreeBuilder::resolve_link_targets` is disabled, in which case they are
+    /// written verbatim. The target does not need to exist.
+    #[cfg_attr(feature = "serde", serde(rename = "symlink"))]
+    Symlink { target: PathBuf },
+    /// A hard link to `target`
+    ///
+    /// Relative targets are always resolved against the tree root and the
+    /// target must exist when the link is created.
+    #[cfg_attr(feature = "serde", serde(rename = "hard_link"))]
+    HardLink { target: PathBuf },
+    /// A file whose content is produced by `generator` while it is written
+    ///
+    /// In YAML the generator is picked with a `generator` key next to its
+    /// fields, e.g. `generator: lorem` and `lines: 10000`.
+    #[cfg_attr(feature = "serde", serde(rename = "generated"))]
+    Generated {
+        #[cfg_attr(feature = "serde", serde(flatten))]
+        generator: crate::Generator,
+    },
+    /// A copy of the file or directory at `source`
+    ///
+    /// Directories are copied recursively and permissions are preserved.
+    /// Symlinks are copied as links unless `follow_symlinks` is set, in which
+    /// case what they point to is copied. Relative sources are resolved
+    /// against the current directory. Copying to the root path (`""`) copies
+    /// the content of a directory into the root folder.
+    #[cfg_attr(feature = "serde", serde(rename = "copy"))]
+    Copy {
+        source: PathBuf,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, skip_serializing_if = "std::ops::Not::not")
+        )]
+        follow_symlinks: bool,
+    },
+}
+
+#[cfg(feature = "serde")]
+mod base64_content {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(content: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(content))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        // Long payloads may be wrapped over several lines in a block scalar.
+        let encoded: String = String::deserialize(deserializer)?
+            .split_whitespace()
+            .collect();
+        STANDARD
+            .decode(encoded)
+            .map_err(|e| D::Error::custom(format!("invalid base64 content: {e}")))
+    }
+}
+
+/// Represents an entry, file or directory, to be created.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Entry {
+    /// Path of the entry relative to the root folder.
+    pub path: PathBuf,
+    /// The kind of the entry
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub kind: Kind,
+    /// Optional settings for the entry
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub settings: Option<Settings>,
+}
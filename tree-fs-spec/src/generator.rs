@@ -0,0 +1,24 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Produces the content of a [`crate::Kind::Generated`] file.
+///
+/// Content is streamed to the file, so large files never need to fit in memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "generator", rename_all = "snake_case"))]
+pub enum Generator {
+    /// `pattern` repeated up to `size` bytes, the last repetition cut short.
+    Repeat { pattern: String, size: u64 },
+    /// `size` pseudo-random bytes, always the same for a given `seed`.
+    Random {
+        size: u64,
+        #[cfg_attr(feature = "serde", serde(default))]
+        seed: u64,
+    },
+    /// `lines` lines of lorem ipsum text, each ending with a newline.
+    Lorem { lines: u64 },
+    /// A file of `size` bytes of zeros, left sparse where the file system
+    /// supports it.
+    Sparse { size: u64 },
+}
@@ -0,0 +1,21 @@
+//! Data model of `tree-fs` trees, re-exported by the `tree-fs` crate.
+//!
+//! With the `serde` feature it also holds [`TreeSpec`], the serialized form
+//! read from YAML, JSON and TOML, so `tree-fs-macros` checks embedded specs
+//! against the same model `tree-fs` loads them with. The checks that need
+//! no file system, such as [`check_conflicts`], live here for the same
+//! reason.
+
+mod check;
+pub use check::{check_conflicts, check_path, normalize, Conflict};
+
+mod entry;
+pub use entry::{DropPolicy, Entry, Kind, Settings, Timestamp};
+
+mod generator;
+pub use generator::Generator;
+
+#[cfg(feature = "serde")]
+mod spec;
+#[cfg(feature = "serde")]
+pub use spec::{Nested, Scalar, TreeSpec};
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{DropPolicy, Entry, Kind};
+
+/// Serialized form of a `tree_fs::TreeBuilder`.
+#[derive(Debug, Deserialize, Serialize)]
+// The flags mirror keys of the file format.
+#[allow(clippy::struct_excessive_bools)]
+pub struct TreeSpec {
+    /// Root folder, a temporary folder is allocated when missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+    /// Whether existing files are overridden.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub override_file: bool,
+    /// Nested form of the entries, expanded before `entries`.
+    #[serde(default, skip_serializing)]
+    pub tree: Nested,
+    /// Entries of the tree.
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+    /// Whether the root folder is deleted on drop.
+    #[serde(default = "default_drop", skip_serializing_if = "is_true")]
+    pub drop: bool,
+    /// Takes precedence over `drop` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drop_policy: Option<DropPolicy>,
+    /// Whether relative symlink targets are resolved against the root folder.
+    #[serde(
+        default = "default_resolve_link_targets",
+        skip_serializing_if = "is_true"
+    )]
+    pub resolve_link_targets: bool,
+    /// Variables substituted in entry paths and text content.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, Scalar>,
+    /// Whether placeholders naming an undefined variable are an error.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_vars: bool,
+    /// Whether placeholders are substituted at all.
+    #[serde(default = "default_templating", skip_serializing_if = "is_true")]
+    pub templating: bool,
+}
+
+/// A variable value, written as a string or any other scalar.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub struct Scalar(pub String);
+
+impl<'de> Deserialize<'de> for Scalar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ScalarVisitor;
+
+        impl Visitor<'_> for ScalarVisitor {
+            type Value = Scalar;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a string, number or boolean")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Scalar, E> {
+                Ok(Scalar(value.to_string()))
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, value: bool) -> Result<Scalar, E> {
+                Ok(Scalar(value.to_string()))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Scalar, E> {
+                Ok(Scalar(value.to_string()))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Scalar, E> {
+                Ok(Scalar(value.to_string()))
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, value: f64) -> Result<Scalar, E> {
+                Ok(Scalar(value.to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(ScalarVisitor)
+    }
+}
+
+/// Entries written as nested mappings: strings are text files, `null` is an
+/// empty file and mappings are directories.
+#[derive(Debug, Default)]
+pub struct Nested(Vec<(PathBuf, Node)>);
+
+#[derive(Debug)]
+enum Node {
+    EmptyFile,
+    TextFile(String),
+    Directory(Nested),
+}
+
+impl Nested {
+    /// Appends the entries in document order, emitting directories only when
+    /// they have no children.
+    pub fn expand(self, parent: &Path, entries: &mut Vec<Entry>) {
+        for (name, node) in self.0 {
+            let path = parent.join(name);
+            let kind = match node {
+                Node::EmptyFile => Kind::EmptyFile,
+                Node::TextFile(content) => Kind::TextFile { content },
+                Node::Directory(children) if children.0.is_empty() => Kind::Directory,
+                Node::Directory(children) => {
+                    children.expand(&path, entries);
+                    continue;
+                }
+            };
+            entries.push(Entry {
+                path,
+                kind,
+                settings: None,
+            });
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Nested {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(NestedVisitor)
+    }
+}
+
+struct NestedVisitor;
+
+impl<'de> Visitor<'de> for NestedVisitor {
+    type Value = Nested;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a mapping of entry names")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Nested, A::Error> {
+        let mut children = Vec::new();
+        while let Some((name, node)) = map.next_entry::<PathBuf, Node>()? {
+            children.push((name, node));
+        }
+        Ok(Nested(children))
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+struct NodeVisitor;
+
+impl<'de> Visitor<'de> for NodeVisitor {
+    type Value = Node;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string (text file), null (empty file) or a mapping (directory)")
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Node, E> {
+        Ok(Node::EmptyFile)
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Node, E> {
+        Ok(Node::EmptyFile)
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Node, E> {
+        Ok(Node::TextFile(value.to_string()))
+    }
+
+    fn visit_string<E: serde::de::Error>(self, value: String) -> Result<Node, E> {
+        Ok(Node::TextFile(value))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Node, A::Error> {
+        NestedVisitor.visit_map(map).map(Node::Directory)
+    }
+}
+
+/// Default is to drop the directory when the Tree is dropped
+const fn default_drop() -> bool {
+    true
+}
+
+/// Default is to resolve relative link targets against the root folder
+const fn default_resolve_link_targets() -> bool {
+    true
+}
+
+/// Default is to substitute variables
+const fn default_templating() -> bool {
+    true
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_true(value: &bool) -> bool {
+    *value
+}